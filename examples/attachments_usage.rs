@@ -30,10 +30,9 @@ async fn main() {
                             "  Related Transaction: {}",
                             attachment.relationships.transaction.data.id
                         );
-                        println!(
-                            "  File URL Expires: {}",
-                            attachment.attributes.file_url_expires_at
-                        );
+                        if let Some(expires_at) = &attachment.attributes.file_url_expires_at {
+                            println!("  File URL Expires: {}", expires_at);
+                        }
                         println!();
                     }
 
@@ -56,6 +55,17 @@ async fn main() {
                                 eprintln!("Failed to get specific attachment: {:?}", e);
                             }
                         }
+
+                        // Download the file into memory
+                        println!("\n=== Downloading attachment ===");
+                        let mut buffer = Vec::new();
+                        match client
+                            .download_attachment(&first_attachment.id, &mut buffer)
+                            .await
+                        {
+                            Ok(bytes) => println!("Downloaded {} bytes", bytes),
+                            Err(e) => eprintln!("Failed to download attachment: {:?}", e),
+                        }
                     }
 
                     // List attachments with pagination limit
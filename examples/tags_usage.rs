@@ -1,5 +1,6 @@
 use dotenv::dotenv;
 use uprusty::prelude::*;
+use uprusty::{RelatedLink, TransactionsResponse};
 
 #[tokio::main]
 async fn main() {
@@ -22,10 +23,38 @@ async fn main() {
                     } else {
                         for tag in &tags.data {
                             println!("Tag: \"{}\"", tag.id);
-                            if let Some(links) = &tag.relationships.transactions.links {
-                                println!("  Transactions URL: {}", links.related);
+                            if let Some(related) = tag.relationships.transactions.links.related() {
+                                println!("  Transactions URL: {}", related);
                             }
                         }
+
+                        // Follow a tag to its transactions
+                        let first_tag = &tags.data[0];
+                        println!("\n=== Transactions tagged \"{}\" ===", first_tag.id);
+                        match client.tag_transactions(&first_tag.id, Some(5), None).await {
+                            Ok(transactions) => {
+                                for tx in &transactions.data {
+                                    println!(
+                                        "- {}: ${}",
+                                        tx.attributes.description, tx.attributes.amount.value
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to list tagged transactions: {:?}", e);
+                            }
+                        }
+
+                        // The same list, reached through the relationship link itself
+                        let related: Result<TransactionsResponse, _> = client
+                            .get_related(&first_tag.relationships.transactions.links)
+                            .await;
+                        if let Ok(transactions) = related {
+                            println!(
+                                "Related link returned {} transactions",
+                                transactions.data.len()
+                            );
+                        }
                     }
 
                     // List tags with pagination
@@ -70,7 +99,7 @@ async fn main() {
                     // If you have some existing tags, show some examples
                     if !tags.data.is_empty() {
                         println!("\n=== Example Tag Names You Could Use ===");
-                        let example_tags = vec![
+                        let example_tags = [
                             "Work Expense",
                             "Tax Deductible",
                             "Holiday",
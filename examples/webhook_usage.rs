@@ -1,7 +1,7 @@
 use dotenv::dotenv;
+use uprusty::WebhookEventResource;
 use uprusty::prelude::*;
-use uprusty::webhook::{WebhookEventHandler, events};
-use uprusty::{WebhookEventResource, WebhookEventType};
+use uprusty::webhook::WebhookEventHandler;
 
 // Example webhook event handler implementation
 struct MyWebhookHandler;
@@ -132,7 +132,7 @@ async fn main() {
 
             // Example 1: Basic signature verification
             let example_secret = "vWmPcH853fS9OAyaspxqzxHoTKVNlDYByBC7F3NA403ETNL1vpkxHSxUlul2Gs8P";
-            let example_body = r#"{"data":{"type":"webhook-events","id":"test","attributes":{"eventType":"PING","createdAt":"2025-07-29T10:00:00+10:00"},"relationships":{"webhook":{"data":{"type":"webhooks","id":"test-webhook"}}}}}"#;
+            let _example_body = r#"{"data":{"type":"webhook-events","id":"test","attributes":{"eventType":"PING","createdAt":"2025-07-29T10:00:00+10:00"},"relationships":{"webhook":{"data":{"type":"webhooks","id":"test-webhook"}}}}}"#;
 
             println!("Example webhook event processing:");
             println!("```rust");
@@ -152,8 +152,8 @@ async fn main() {
             println!("```");
 
             // Example 2: Webhook handler usage
-            let webhook_handler = WebhookHandler::new(example_secret);
-            let my_event_handler = MyWebhookHandler;
+            let _webhook_handler = WebhookHandler::new(example_secret);
+            let _my_event_handler = MyWebhookHandler;
 
             println!("\n=== Event Handler Example ===");
             println!("This is how you would handle webhook events in your server:");
@@ -0,0 +1,30 @@
+pub mod balance;
+pub mod budget;
+pub mod forecast;
+pub mod fx;
+pub mod lifecycle;
+pub mod recurring;
+pub mod roundup;
+
+pub use balance::{
+    BalanceHistory, BalanceInconsistency, DailyBalance, fetch_balance_history,
+    reconstruct_balance_history,
+};
+pub use budget::{
+    Budget, BudgetAlert, BudgetMonitor, BudgetScope, BudgetStatus, BudgetTracker,
+    BudgetWebhookHandler,
+};
+pub use forecast::{
+    AccountForecast, CashflowForecaster, ForecastDay, ForecastEvent, ForecastEventKind,
+};
+pub use fx::{
+    CurrencySummary, ForeignSpendingReport, ForeignTransaction, HoldComparison,
+    fetch_foreign_spending, foreign_spending,
+};
+pub use lifecycle::{
+    LifecycleState, LifecycleTracker, LifecycleWebhookHandler, StateChange, TransactionLifecycle,
+};
+pub use recurring::{Frequency, PriceChange, RecurringDetector, RecurringPayment, UpcomingCharge};
+pub use roundup::{
+    Period, RoundUpAnalyzer, RoundUpMatch, RoundUpPeriodTotal, RoundUpReport, SaverRoundUpTotal,
+};
@@ -0,0 +1,212 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::accounts::AccountsExt;
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::account::{AccountResource, AccountType};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::types::money::MoneyObject;
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, TimeDelta, Utc};
+use std::collections::BTreeMap;
+
+// `filter[since]` selects by creation, but balances change on settlement, so transactions
+// are fetched from this long before the history starts to catch those settled later.
+const SETTLEMENT_LOOKBACK: TimeDelta = TimeDelta::days(30);
+
+#[derive(Debug, Clone)]
+pub struct DailyBalance {
+    pub date: NaiveDate,
+    /// Settled balance at the end of the day.
+    pub balance: MoneyObject,
+}
+
+#[derive(Debug, Clone)]
+pub enum BalanceInconsistency {
+    /// The history reaches back to the account's creation, but the balance before the first
+    /// transaction is not zero, so some transactions are missing.
+    UnexplainedOpeningBalance { opening_balance: MoneyObject },
+    /// A saver account would have been overdrawn at the end of this day, which Up does not
+    /// allow, so transactions are missing around this date.
+    NegativeSaverBalance {
+        date: NaiveDate,
+        balance: MoneyObject,
+    },
+    /// A settled transaction without a `settled_at`; its creation date was used instead.
+    MissingSettlementDate { transaction_id: String },
+    /// A transaction timestamp could not be parsed and the transaction was ignored.
+    InvalidTimestamp { transaction_id: String },
+}
+
+/// End-of-day settled balances of an account, reconstructed from its current balance.
+#[derive(Debug, Clone)]
+pub struct BalanceHistory {
+    pub account_id: String,
+    /// Settled balance before the first day of the history.
+    pub opening_balance: MoneyObject,
+    /// One entry per calendar day, oldest first, up to and including today.
+    pub days: Vec<DailyBalance>,
+    pub inconsistencies: Vec<BalanceInconsistency>,
+}
+
+impl BalanceHistory {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    pub fn on(&self, date: NaiveDate) -> Option<&MoneyObject> {
+        self.days
+            .binary_search_by_key(&date, |d| d.date)
+            .ok()
+            .map(|index| &self.days[index].balance)
+    }
+}
+
+/// Reconstruct an account's daily balances by walking its settled transactions backwards
+/// from the current balance.
+///
+/// `transactions` should contain every transaction of the account settled since the start
+/// of the wanted history, including held ones, whose amounts are already reflected in
+/// `balance`. Transactions of other accounts are ignored.
+pub fn reconstruct_balance_history(
+    account: &AccountResource,
+    transactions: &[TransactionResource],
+) -> BalanceHistory {
+    let balance = &account.attributes.balance;
+    let mut inconsistencies = Vec::new();
+    let mut settled_balance = balance.value_in_base_units;
+    let mut daily_totals: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    let mut offset = parse_timestamp(&account.attributes.created_at).map(|d| *d.offset());
+
+    for transaction in transactions {
+        if transaction.relationships.account.data.id != account.id {
+            continue;
+        }
+        let attributes = &transaction.attributes;
+        let amount = attributes.amount.value_in_base_units;
+
+        if matches!(attributes.status, TransactionStatus::Held) {
+            settled_balance -= amount;
+            continue;
+        }
+
+        let settled_at = match &attributes.settled_at {
+            Some(settled_at) => settled_at,
+            None => {
+                inconsistencies.push(BalanceInconsistency::MissingSettlementDate {
+                    transaction_id: transaction.id.clone(),
+                });
+                &attributes.created_at
+            }
+        };
+        match parse_timestamp(settled_at) {
+            Some(settled_at) => {
+                offset = Some(*settled_at.offset());
+                *daily_totals.entry(settled_at.date_naive()).or_default() += amount;
+            }
+            None => inconsistencies.push(BalanceInconsistency::InvalidTimestamp {
+                transaction_id: transaction.id.clone(),
+            }),
+        }
+    }
+
+    // "Today" in Up's timezone, as seen in the data, rather than the local machine's.
+    let offset = offset.unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    let today = Utc::now().with_timezone(&offset).date_naive();
+    let first_day = daily_totals.keys().next().copied().unwrap_or(today);
+    let last_day = daily_totals
+        .keys()
+        .next_back()
+        .copied()
+        .unwrap_or(today)
+        .max(today);
+
+    let mut days = Vec::new();
+    let mut running = settled_balance;
+    let mut date = last_day;
+    loop {
+        days.push(DailyBalance {
+            date,
+            balance: balance.with_base_units(running),
+        });
+        running -= daily_totals.get(&date).copied().unwrap_or_default();
+        if date <= first_day {
+            break;
+        }
+        date = date - Days::new(1);
+    }
+    days.reverse();
+
+    if let AccountType::Saver = account.attributes.account_type {
+        for day in &days {
+            if day.balance.value_in_base_units < 0 {
+                inconsistencies.push(BalanceInconsistency::NegativeSaverBalance {
+                    date: day.date,
+                    balance: day.balance.clone(),
+                });
+            }
+        }
+    }
+
+    let created = parse_timestamp(&account.attributes.created_at).map(|d| d.date_naive());
+    if created.is_some_and(|created| first_day <= created) && running != 0 {
+        inconsistencies.push(BalanceInconsistency::UnexplainedOpeningBalance {
+            opening_balance: balance.with_base_units(running),
+        });
+    }
+
+    BalanceHistory {
+        account_id: account.id.clone(),
+        opening_balance: balance.with_base_units(running),
+        days,
+        inconsistencies,
+    }
+}
+
+/// Fetch an account and all of its transactions since `since` (RFC 3339, or the whole
+/// history when `None`) and reconstruct its daily balances.
+///
+/// Transactions created up to 30 days before `since` are fetched too, so that those
+/// settled after `since` are counted; the history is then trimmed to start on the day of
+/// `since`.
+pub async fn fetch_balance_history(
+    client: &Client,
+    account_id: &str,
+    since: Option<&str>,
+) -> Result<BalanceHistory, ClientError> {
+    let since = since.map(|since| (since, parse_timestamp(since)));
+    let account = client.get_account(account_id).await?.data;
+    let filters = TransactionFilters {
+        since: since.map(|(since, parsed)| match parsed {
+            Some(parsed) => (parsed - SETTLEMENT_LOOKBACK).to_rfc3339(),
+            None => since.to_string(),
+        }),
+        ..Default::default()
+    };
+    let transactions = client
+        .list_all_account_transactions(account_id, Some(filters))
+        .await?;
+
+    let history = reconstruct_balance_history(&account, &transactions);
+    Ok(match since.and_then(|(_, parsed)| parsed) {
+        Some(since) => trim(history, since.date_naive()),
+        None => history,
+    })
+}
+
+// Drop the days before `first_day`, and the negative balances found on them.
+fn trim(mut history: BalanceHistory, first_day: NaiveDate) -> BalanceHistory {
+    let start = history.days.partition_point(|day| day.date < first_day);
+    if start > 0 {
+        history.opening_balance = history.days[start - 1].balance.clone();
+        history.days.drain(..start);
+        history
+            .inconsistencies
+            .retain(|inconsistency| match inconsistency {
+                BalanceInconsistency::NegativeSaverBalance { date, .. } => *date >= first_day,
+                _ => true,
+            });
+    }
+    history
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(timestamp).ok()
+}
@@ -0,0 +1,439 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::transaction::TransactionResource;
+use crate::models::webhooks::WebhookEventResource;
+use crate::webhook::WebhookEventHandler;
+use crate::webhook::fetcher::TransactionFetcher;
+use chrono::{DateTime, Datelike, FixedOffset, Months, NaiveDate, NaiveTime};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::runtime::Handle;
+
+const DEFAULT_THRESHOLDS: [f64; 2] = [0.8, 1.0];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetScope {
+    Category(String),
+    /// Every category under a parent, e.g. `good-life`.
+    ParentCategory(String),
+}
+
+/// A monthly spending limit for a category or parent category.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    pub name: String,
+    pub scope: BudgetScope,
+    /// Monthly limit in base units, as a positive number.
+    pub limit_in_base_units: i64,
+    /// Fractions of the limit that raise an alert when crossed, 80% and 100% by default.
+    pub thresholds: Vec<f64>,
+}
+
+impl Budget {
+    pub fn category(category_id: &str, limit_in_base_units: i64) -> Self {
+        Self::new(
+            BudgetScope::Category(category_id.to_string()),
+            limit_in_base_units,
+        )
+    }
+
+    pub fn parent_category(parent_category_id: &str, limit_in_base_units: i64) -> Self {
+        Self::new(
+            BudgetScope::ParentCategory(parent_category_id.to_string()),
+            limit_in_base_units,
+        )
+    }
+
+    fn new(scope: BudgetScope, limit_in_base_units: i64) -> Self {
+        let name = match &scope {
+            BudgetScope::Category(id) | BudgetScope::ParentCategory(id) => id.clone(),
+        };
+        Self {
+            name,
+            scope,
+            limit_in_base_units,
+            thresholds: DEFAULT_THRESHOLDS.to_vec(),
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn thresholds(mut self, thresholds: &[f64]) -> Self {
+        self.thresholds = thresholds.to_vec();
+        self
+    }
+
+    pub fn covers(&self, transaction: &TransactionResource) -> bool {
+        let relationships = &transaction.relationships;
+        let (wanted, actual) = match &self.scope {
+            BudgetScope::Category(id) => (id, relationships.category.data.as_ref()),
+            BudgetScope::ParentCategory(id) => (id, relationships.parent_category.data.as_ref()),
+        };
+        actual.is_some_and(|actual| actual.id == *wanted)
+    }
+}
+
+/// Progress of one budget through a month. Amounts are in base units.
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub budget: Budget,
+    /// First day of the month the status covers.
+    pub month: NaiveDate,
+    /// Spending less refunds in the budget's categories, as a positive number.
+    pub spent_in_base_units: i64,
+    /// Negative once the budget is overspent.
+    pub remaining_in_base_units: i64,
+    /// Spending by the end of the month if it continues at the current daily rate.
+    pub projected_in_base_units: i64,
+}
+
+impl BudgetStatus {
+    /// Spending as a fraction of the limit.
+    pub fn used(&self) -> f64 {
+        used(self.spent_in_base_units, self.budget.limit_in_base_units)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining_in_base_units < 0
+    }
+
+    /// How far the projected spending exceeds the limit, if it does.
+    pub fn projected_overspend_in_base_units(&self) -> Option<i64> {
+        let overspend = self.projected_in_base_units - self.budget.limit_in_base_units;
+        (overspend > 0).then_some(overspend)
+    }
+}
+
+/// A budget crossing one of its thresholds.
+#[derive(Debug, Clone)]
+pub struct BudgetAlert {
+    pub budget: String,
+    pub threshold: f64,
+    pub spent_in_base_units: i64,
+    pub limit_in_base_units: i64,
+    /// The transaction that pushed the budget over the threshold.
+    pub transaction_id: String,
+}
+
+/// Compares a month's transactions against a set of budgets.
+#[derive(Debug, Clone, Default)]
+pub struct BudgetTracker {
+    budgets: Vec<Budget>,
+}
+
+impl BudgetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn budget(mut self, budget: Budget) -> Self {
+        self.budgets.push(budget);
+        self
+    }
+
+    pub fn budgets(&self) -> &[Budget] {
+        &self.budgets
+    }
+
+    /// Status of every budget for the month containing `today`. Transactions from other
+    /// months are ignored, so a longer history can be passed in.
+    pub fn status(
+        &self,
+        transactions: &[TransactionResource],
+        today: NaiveDate,
+    ) -> Vec<BudgetStatus> {
+        let month = month_start(today);
+        let mut spent = vec![0; self.budgets.len()];
+        for transaction in transactions {
+            if transaction_month(transaction) != Some(month) {
+                continue;
+            }
+            for (index, budget) in self.budgets.iter().enumerate() {
+                if budget.covers(transaction) {
+                    spent[index] -= transaction.attributes.amount.value_in_base_units;
+                }
+            }
+        }
+
+        let days_in_month = (next_month(month) - month).num_days();
+        let elapsed = i64::from(today.day());
+        self.budgets
+            .iter()
+            .zip(spent)
+            .map(|(budget, spent)| BudgetStatus {
+                budget: budget.clone(),
+                month,
+                spent_in_base_units: spent,
+                remaining_in_base_units: budget.limit_in_base_units - spent,
+                projected_in_base_units: spent * days_in_month / elapsed,
+            })
+            .collect()
+    }
+
+    /// Fetch this month's transactions and report on every budget.
+    pub async fn fetch_status(
+        &self,
+        client: &Client,
+        today: NaiveDate,
+    ) -> Result<Vec<BudgetStatus>, ClientError> {
+        let transactions = fetch_month(client, today).await?;
+        Ok(self.status(&transactions, today))
+    }
+}
+
+/// Keeps running budget totals for the current month and raises alerts as new
+/// transactions arrive.
+///
+/// A transaction seen again, e.g. when it settles or is recategorized, replaces what it
+/// earlier added to each budget rather than being counted twice. Totals start over when a
+/// transaction from a later month arrives.
+#[derive(Debug)]
+pub struct BudgetMonitor {
+    tracker: BudgetTracker,
+    state: Mutex<MonitorState>,
+}
+
+#[derive(Debug)]
+struct MonitorState {
+    month: NaiveDate,
+    spent: Vec<i64>,
+    /// What each transaction added to each budget, as `(budget index, amount)`.
+    seen: HashMap<String, Vec<(usize, i64)>>,
+}
+
+impl MonitorState {
+    fn forget(&mut self, transaction_id: &str) {
+        for (index, amount) in self.seen.remove(transaction_id).unwrap_or_default() {
+            self.spent[index] -= amount;
+        }
+    }
+}
+
+impl BudgetMonitor {
+    /// Start from the transactions already made this month.
+    pub fn new(
+        tracker: BudgetTracker,
+        transactions: &[TransactionResource],
+        today: NaiveDate,
+    ) -> Self {
+        let month = month_start(today);
+        let monitor = Self {
+            state: Mutex::new(MonitorState {
+                month,
+                spent: vec![0; tracker.budgets.len()],
+                seen: HashMap::new(),
+            }),
+            tracker,
+        };
+        for transaction in transactions {
+            monitor.observe(transaction);
+        }
+        monitor
+    }
+
+    /// Fetch this month's transactions to start from.
+    pub async fn fetch(
+        tracker: BudgetTracker,
+        client: &Client,
+        today: NaiveDate,
+    ) -> Result<Self, ClientError> {
+        let transactions = fetch_month(client, today).await?;
+        Ok(Self::new(tracker, &transactions, today))
+    }
+
+    /// Add a new or updated transaction to the totals, returning any thresholds it crossed.
+    pub fn observe(&self, transaction: &TransactionResource) -> Vec<BudgetAlert> {
+        let Some(month) = transaction_month(transaction) else {
+            return Vec::new();
+        };
+        let mut state = self.lock();
+        if month < state.month {
+            return Vec::new();
+        }
+        if month > state.month {
+            state.month = month;
+            state.spent.iter_mut().for_each(|spent| *spent = 0);
+            state.seen.clear();
+        }
+
+        let before = state.spent.clone();
+        state.forget(&transaction.id);
+
+        let amount = -transaction.attributes.amount.value_in_base_units;
+        let mut contributions = Vec::new();
+        for (index, budget) in self.tracker.budgets.iter().enumerate() {
+            if budget.covers(transaction) {
+                state.spent[index] += amount;
+                contributions.push((index, amount));
+            }
+        }
+        state.seen.insert(transaction.id.clone(), contributions);
+
+        let mut alerts = Vec::new();
+        for (index, budget) in self.tracker.budgets.iter().enumerate() {
+            let after = state.spent[index];
+            let limit = budget.limit_in_base_units;
+            for threshold in &budget.thresholds {
+                if used(before[index], limit) < *threshold && used(after, limit) >= *threshold {
+                    alerts.push(BudgetAlert {
+                        budget: budget.name.clone(),
+                        threshold: *threshold,
+                        spent_in_base_units: after,
+                        limit_in_base_units: limit,
+                        transaction_id: transaction.id.clone(),
+                    });
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Stop counting a transaction, e.g. a hold that was released without settling.
+    pub fn remove(&self, transaction_id: &str) {
+        self.lock().forget(transaction_id);
+    }
+
+    /// Current month's spending per budget, in the order the budgets were added.
+    pub fn spent(&self) -> Vec<(String, i64)> {
+        let state = self.lock();
+        self.tracker
+            .budgets
+            .iter()
+            .zip(&state.spent)
+            .map(|(budget, spent)| (budget.name.clone(), *spent))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MonitorState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+type AlertCallback = dyn Fn(BudgetAlert) + Send + Sync;
+type ErrorCallback = dyn Fn(&str, ClientError) + Send + Sync;
+
+/// Feeds transactions reported by a webhook into a [`BudgetMonitor`].
+///
+/// Each transaction is fetched in the background, and `on_alert` is called for every
+/// threshold it crosses. Deleted transactions, such as released holds, stop counting
+/// straight away.
+#[derive(Clone)]
+pub struct BudgetWebhookHandler {
+    monitor: Arc<BudgetMonitor>,
+    fetcher: TransactionFetcher,
+    on_alert: Arc<AlertCallback>,
+    on_error: Option<Arc<ErrorCallback>>,
+}
+
+impl fmt::Debug for BudgetWebhookHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BudgetWebhookHandler")
+            .field("monitor", &self.monitor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BudgetWebhookHandler {
+    /// Transactions are fetched on `runtime`.
+    pub fn new<F>(monitor: Arc<BudgetMonitor>, client: Client, runtime: Handle, on_alert: F) -> Self
+    where
+        F: Fn(BudgetAlert) + Send + Sync + 'static,
+    {
+        Self {
+            monitor,
+            fetcher: TransactionFetcher::new(client, runtime),
+            on_alert: Arc::new(on_alert),
+            on_error: None,
+        }
+    }
+
+    /// Called when a transaction from a webhook event could not be fetched.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str, ClientError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    fn spawn_observe(&self, transaction_id: &str) {
+        let monitor = Arc::clone(&self.monitor);
+        let on_alert = Arc::clone(&self.on_alert);
+        let on_error = self.on_error.clone();
+
+        self.fetcher
+            .spawn(transaction_id, |_, transaction_id, result| async move {
+                match result {
+                    Ok(transaction) => {
+                        for alert in monitor.observe(&transaction) {
+                            on_alert(alert);
+                        }
+                    }
+                    Err(e) => {
+                        if let Some(callback) = on_error {
+                            callback(&transaction_id, e);
+                        }
+                    }
+                }
+            });
+    }
+}
+
+impl WebhookEventHandler for BudgetWebhookHandler {
+    fn on_transaction_created(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.spawn_observe(transaction_id);
+    }
+
+    fn on_transaction_settled(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.spawn_observe(transaction_id);
+    }
+
+    fn on_transaction_deleted(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.monitor.remove(transaction_id);
+    }
+}
+
+async fn fetch_month(
+    client: &Client,
+    today: NaiveDate,
+) -> Result<Vec<TransactionResource>, ClientError> {
+    // A day early, so transactions made late on the last day of the previous month in a
+    // timezone behind ours are not missed; they are filtered out by month afterwards.
+    let since = month_start(today)
+        .pred_opt()
+        .unwrap_or(NaiveDate::MIN)
+        .and_time(NaiveTime::MIN)
+        .and_utc();
+    let filters = TransactionFilters {
+        since: Some(since.to_rfc3339()),
+        ..Default::default()
+    };
+    client.list_all_transactions(Some(filters)).await
+}
+
+fn transaction_month(transaction: &TransactionResource) -> Option<NaiveDate> {
+    DateTime::<FixedOffset>::parse_from_rfc3339(&transaction.attributes.created_at)
+        .ok()
+        .map(|created| month_start(created.date_naive()))
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn next_month(month: NaiveDate) -> NaiveDate {
+    month
+        .checked_add_months(Months::new(1))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+fn used(spent: i64, limit: i64) -> f64 {
+    if limit <= 0 {
+        return if spent > 0 { f64::INFINITY } else { 0.0 };
+    }
+    spent as f64 / limit as f64
+}
@@ -0,0 +1,241 @@
+use crate::analysis::recurring::{RecurringDetector, RecurringPayment};
+use crate::client::{Client, ClientError};
+use crate::endpoints::accounts::AccountsExt;
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::account::{AccountResource, AccountsResponse};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::types::money::MoneyObject;
+use chrono::{DateTime, Days, NaiveDate};
+
+#[derive(Debug, Clone)]
+pub enum ForecastEventKind {
+    /// An expected occurrence of a recurring payment or income.
+    Recurring { confidence: f64 },
+    /// A held transaction expected to settle. Its amount is already deducted from the
+    /// available balance, so it does not move the projection.
+    HeldSettlement { transaction_id: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct ForecastEvent {
+    pub description: String,
+    pub amount: MoneyObject,
+    pub kind: ForecastEventKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForecastDay {
+    pub date: NaiveDate,
+    /// Projected available balance at the end of the day.
+    pub balance: MoneyObject,
+    pub events: Vec<ForecastEvent>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountForecast {
+    pub account_id: String,
+    /// Current available balance, with held transactions already deducted.
+    pub starting_balance: MoneyObject,
+    /// Total of the account's held transactions.
+    pub held: MoneyObject,
+    /// One entry per day, starting today.
+    pub days: Vec<ForecastDay>,
+}
+
+impl AccountForecast {
+    pub fn lowest(&self) -> Option<&ForecastDay> {
+        self.days
+            .iter()
+            .min_by_key(|day| day.balance.value_in_base_units)
+    }
+
+    /// The first day the projected balance drops below `threshold_in_base_units`.
+    pub fn first_below(&self, threshold_in_base_units: i64) -> Option<&ForecastDay> {
+        self.days
+            .iter()
+            .find(|day| day.balance.value_in_base_units < threshold_in_base_units)
+    }
+}
+
+/// Projects account balances forward from the current balance using recurring income and
+/// expenses detected in transaction history.
+#[derive(Debug, Clone)]
+pub struct CashflowForecaster {
+    detector: RecurringDetector,
+    days: u64,
+    settlement_days: u64,
+}
+
+impl Default for CashflowForecaster {
+    fn default() -> Self {
+        Self {
+            detector: RecurringDetector::new().include_transfers(true),
+            days: 30,
+            settlement_days: 3,
+        }
+    }
+}
+
+impl CashflowForecaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of days to project after today.
+    pub fn days(mut self, days: u64) -> Self {
+        self.days = days;
+        self
+    }
+
+    /// How long after creation held transactions are expected to settle.
+    pub fn settlement_days(mut self, settlement_days: u64) -> Self {
+        self.settlement_days = settlement_days;
+        self
+    }
+
+    pub fn detector(mut self, detector: RecurringDetector) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    /// Forecast every account from its transaction history, which should cover several
+    /// months so salary and monthly bills can be detected.
+    pub fn forecast(
+        &self,
+        accounts: &[AccountResource],
+        transactions: &[TransactionResource],
+        today: NaiveDate,
+    ) -> Vec<AccountForecast> {
+        let recurring: Vec<RecurringPayment> = self
+            .detector
+            .detect(transactions)
+            .into_iter()
+            .filter(|payment| !payment.is_lapsed(today))
+            .collect();
+
+        accounts
+            .iter()
+            .map(|account| self.forecast_account(account, transactions, &recurring, today))
+            .collect()
+    }
+
+    /// Fetch every account and all transactions since `since` (RFC 3339), then forecast.
+    pub async fn fetch(
+        &self,
+        client: &Client,
+        since: &str,
+        today: NaiveDate,
+    ) -> Result<Vec<AccountForecast>, ClientError> {
+        let mut page = client.list_accounts(Some(100), None, None).await?;
+        let mut accounts = std::mem::take(&mut page.data);
+        while let Some(next) = page.links.next.take() {
+            page = client.get_page::<AccountsResponse>(&next).await?;
+            accounts.append(&mut page.data);
+        }
+
+        let filters = TransactionFilters {
+            since: Some(since.to_string()),
+            ..Default::default()
+        };
+        let transactions = client.list_all_transactions(Some(filters)).await?;
+
+        Ok(self.forecast(&accounts, &transactions, today))
+    }
+
+    fn forecast_account(
+        &self,
+        account: &AccountResource,
+        transactions: &[TransactionResource],
+        recurring: &[RecurringPayment],
+        today: NaiveDate,
+    ) -> AccountForecast {
+        let balance = &account.attributes.balance;
+        let until = today
+            .checked_add_days(Days::new(self.days))
+            .unwrap_or(NaiveDate::MAX);
+
+        let mut days: Vec<ForecastDay> = Vec::new();
+        let mut date = today;
+        while date <= until {
+            days.push(ForecastDay {
+                date,
+                balance: balance.clone(),
+                events: Vec::new(),
+            });
+            match date.succ_opt() {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+
+        let mut held_total = 0;
+        for transaction in transactions {
+            let attributes = &transaction.attributes;
+            if transaction.relationships.account.data.id != account.id
+                || !matches!(attributes.status, TransactionStatus::Held)
+            {
+                continue;
+            }
+            held_total += attributes.amount.value_in_base_units;
+
+            let expected = DateTime::parse_from_rfc3339(&attributes.created_at)
+                .ok()
+                .and_then(|created| {
+                    created
+                        .date_naive()
+                        .checked_add_days(Days::new(self.settlement_days))
+                })
+                .unwrap_or(today)
+                .max(today);
+            let amount = attributes
+                .hold_info
+                .as_ref()
+                .map_or(&attributes.amount, |hold| &hold.amount);
+            if let Some(day) = days.iter_mut().find(|day| day.date == expected) {
+                day.events.push(ForecastEvent {
+                    description: attributes.description.clone(),
+                    amount: amount.clone(),
+                    kind: ForecastEventKind::HeldSettlement {
+                        transaction_id: transaction.id.clone(),
+                    },
+                });
+            }
+        }
+
+        for payment in recurring.iter().filter(|p| p.account_id == account.id) {
+            let mut dates = payment.occurrences(today, until);
+            // Overdue but not lapsed: it may still arrive, so expect it today.
+            if payment.next_expected < today && dates.first() != Some(&today) {
+                dates.insert(0, today);
+            }
+            for expected in dates {
+                if let Some(day) = days.iter_mut().find(|day| day.date == expected) {
+                    day.events.push(ForecastEvent {
+                        description: payment.description.clone(),
+                        amount: payment.amount.clone(),
+                        kind: ForecastEventKind::Recurring {
+                            confidence: payment.confidence,
+                        },
+                    });
+                }
+            }
+        }
+
+        let mut running = balance.value_in_base_units;
+        for day in &mut days {
+            for event in &day.events {
+                if let ForecastEventKind::Recurring { .. } = event.kind {
+                    running += event.amount.value_in_base_units;
+                }
+            }
+            day.balance = balance.with_base_units(running);
+        }
+
+        AccountForecast {
+            account_id: account.id.clone(),
+            starting_balance: balance.clone(),
+            held: balance.with_base_units(held_total),
+            days,
+        }
+    }
+}
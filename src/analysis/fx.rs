@@ -0,0 +1,182 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::types::money::MoneyObject;
+use chrono::{DateTime, NaiveDate};
+use std::collections::BTreeMap;
+
+/// How a settled transaction compares with its authorisation.
+#[derive(Debug, Clone)]
+pub struct HoldComparison {
+    pub held_amount: MoneyObject,
+    pub held_foreign_amount: MoneyObject,
+    pub held_rate: f64,
+    /// How much more the transaction cost at settlement than when it was authorised, in
+    /// base units of the local currency; negative when it turned out cheaper.
+    pub drift_in_base_units: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignTransaction {
+    pub transaction_id: String,
+    pub description: String,
+    pub date: NaiveDate,
+    pub settled: bool,
+    pub amount: MoneyObject,
+    pub foreign_amount: MoneyObject,
+    /// Local currency paid per unit of the foreign currency, e.g. AUD per USD.
+    pub rate: f64,
+    /// Set for settled transactions whose hold recorded a foreign amount.
+    pub hold: Option<HoldComparison>,
+}
+
+impl ForeignTransaction {
+    /// Change in the effective rate between authorisation and settlement.
+    pub fn rate_drift(&self) -> Option<f64> {
+        self.hold.as_ref().map(|hold| self.rate - hold.held_rate)
+    }
+}
+
+/// Spending in one foreign currency. Totals are in base units and net of refunds, with
+/// spending negative as in the transactions themselves.
+#[derive(Debug, Clone)]
+pub struct CurrencySummary {
+    pub currency_code: String,
+    pub transactions: usize,
+    pub total_in_base_units: i64,
+    pub foreign_total_in_base_units: i64,
+    /// Rate across all transactions, weighted by amount.
+    pub average_rate: f64,
+    pub lowest_rate: f64,
+    pub highest_rate: f64,
+    /// Sum of the settled transactions' drift; positive means settlement cost more.
+    pub drift_in_base_units: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignSpendingReport {
+    /// Sorted by currency code.
+    pub currencies: Vec<CurrencySummary>,
+    /// Oldest first.
+    pub transactions: Vec<ForeignTransaction>,
+}
+
+impl ForeignSpendingReport {
+    /// Transactions made between `from` and `to` inclusive, such as the days of a trip.
+    pub fn between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Iterator<Item = &ForeignTransaction> {
+        self.transactions
+            .iter()
+            .filter(move |t| t.date >= from && t.date <= to)
+    }
+}
+
+/// Summarise transactions made in a foreign currency.
+pub fn foreign_spending(transactions: &[TransactionResource]) -> ForeignSpendingReport {
+    let mut foreign: Vec<ForeignTransaction> = transactions
+        .iter()
+        .filter_map(foreign_transaction)
+        .collect();
+    foreign.sort_by_key(|t| t.date);
+
+    let mut currencies: BTreeMap<&str, CurrencySummary> = BTreeMap::new();
+    let mut weighted: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for transaction in &foreign {
+        let code = transaction.foreign_amount.currency_code.as_str();
+        let summary = currencies.entry(code).or_insert_with(|| CurrencySummary {
+            currency_code: code.to_string(),
+            transactions: 0,
+            total_in_base_units: 0,
+            foreign_total_in_base_units: 0,
+            average_rate: 0.0,
+            lowest_rate: f64::INFINITY,
+            highest_rate: 0.0,
+            drift_in_base_units: 0,
+        });
+        summary.transactions += 1;
+        summary.total_in_base_units += transaction.amount.value_in_base_units;
+        summary.foreign_total_in_base_units += transaction.foreign_amount.value_in_base_units;
+        summary.lowest_rate = summary.lowest_rate.min(transaction.rate);
+        summary.highest_rate = summary.highest_rate.max(transaction.rate);
+        if let Some(hold) = &transaction.hold {
+            summary.drift_in_base_units += hold.drift_in_base_units;
+        }
+
+        let (local, foreign) = weighted.entry(code).or_default();
+        *local += decimal(&transaction.amount).abs();
+        *foreign += decimal(&transaction.foreign_amount).abs();
+    }
+    for (code, summary) in &mut currencies {
+        let (local, foreign) = weighted[code];
+        summary.average_rate = if foreign > 0.0 { local / foreign } else { 0.0 };
+    }
+
+    ForeignSpendingReport {
+        currencies: currencies.into_values().collect(),
+        transactions: foreign,
+    }
+}
+
+/// Fetch all transactions since `since` (RFC 3339) and summarise the foreign ones.
+pub async fn fetch_foreign_spending(
+    client: &Client,
+    since: &str,
+) -> Result<ForeignSpendingReport, ClientError> {
+    let filters = TransactionFilters {
+        since: Some(since.to_string()),
+        ..Default::default()
+    };
+    let transactions = client.list_all_transactions(Some(filters)).await?;
+    Ok(foreign_spending(&transactions))
+}
+
+fn foreign_transaction(transaction: &TransactionResource) -> Option<ForeignTransaction> {
+    let attributes = &transaction.attributes;
+    let foreign_amount = attributes.foreign_amount.as_ref()?;
+    let rate = effective_rate(&attributes.amount, foreign_amount)?;
+    let date = DateTime::parse_from_rfc3339(&attributes.created_at)
+        .ok()?
+        .date_naive();
+    let settled = matches!(attributes.status, TransactionStatus::Settled);
+
+    let hold = attributes
+        .hold_info
+        .as_ref()
+        .filter(|_| settled)
+        .and_then(|hold| {
+            let held_foreign_amount = hold.foreign_amount.as_ref()?;
+            if held_foreign_amount.currency_code != foreign_amount.currency_code {
+                return None;
+            }
+            Some(HoldComparison {
+                held_rate: effective_rate(&hold.amount, held_foreign_amount)?,
+                drift_in_base_units: hold.amount.value_in_base_units
+                    - attributes.amount.value_in_base_units,
+                held_amount: hold.amount.clone(),
+                held_foreign_amount: held_foreign_amount.clone(),
+            })
+        });
+
+    Some(ForeignTransaction {
+        transaction_id: transaction.id.clone(),
+        description: attributes.description.clone(),
+        date,
+        settled,
+        amount: attributes.amount.clone(),
+        foreign_amount: foreign_amount.clone(),
+        rate,
+        hold,
+    })
+}
+
+fn effective_rate(local: &MoneyObject, foreign: &MoneyObject) -> Option<f64> {
+    let foreign = decimal(foreign).abs();
+    (foreign > 0.0).then(|| decimal(local).abs() / foreign)
+}
+
+fn decimal(money: &MoneyObject) -> f64 {
+    money.value_in_base_units as f64 / 10f64.powi(money.decimal_places() as i32)
+}
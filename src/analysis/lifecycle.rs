@@ -0,0 +1,310 @@
+use crate::client::{Client, ClientError};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::models::webhooks::WebhookEventResource;
+use crate::types::money::MoneyObject;
+use crate::webhook::WebhookEventHandler;
+use crate::webhook::fetcher::TransactionFetcher;
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleState {
+    Held,
+    Settled,
+    /// The transaction disappeared, usually a hold that was released without settling.
+    Deleted,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateChange {
+    pub state: LifecycleState,
+    /// When the change was observed: the webhook event's time, or the time passed in when
+    /// polling.
+    pub observed_at: DateTime<FixedOffset>,
+    /// Amount at the time of the change; not known for deletions.
+    pub amount: Option<MoneyObject>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionLifecycle {
+    pub transaction_id: String,
+    pub description: String,
+    pub account_id: String,
+    pub created_at: Option<DateTime<FixedOffset>>,
+    /// Oldest first; only changes of state are recorded.
+    pub history: Vec<StateChange>,
+    /// Amount while held, from `hold_info` or from when the transaction was seen held.
+    pub held_amount: Option<MoneyObject>,
+    /// Amount once settled.
+    pub settled_amount: Option<MoneyObject>,
+}
+
+impl TransactionLifecycle {
+    pub fn state(&self) -> Option<LifecycleState> {
+        self.history.last().map(|change| change.state)
+    }
+
+    /// Settled amount less held amount, in base units. Negative when the final charge
+    /// was larger than the hold, e.g. after a tip.
+    pub fn amount_change_in_base_units(&self) -> Option<i64> {
+        let held = self.held_amount.as_ref()?;
+        let settled = self.settled_amount.as_ref()?;
+        Some(settled.value_in_base_units - held.value_in_base_units)
+    }
+
+    /// Held, then deleted without ever settling.
+    pub fn never_settled(&self) -> bool {
+        self.state() == Some(LifecycleState::Deleted) && self.settled_amount.is_none()
+    }
+
+    fn held_since(&self) -> Option<DateTime<FixedOffset>> {
+        match self.history.last() {
+            Some(change) if change.state == LifecycleState::Held => Some(change.observed_at),
+            _ => None,
+        }
+    }
+}
+
+/// Records how transactions move from held to settled (or disappear), fed either by
+/// polling or by webhook events.
+///
+/// The tracker is shared between threads behind a lock, so it can be updated from a
+/// webhook handler while being queried elsewhere.
+#[derive(Debug, Default)]
+pub struct LifecycleTracker {
+    transactions: Mutex<HashMap<String, TransactionLifecycle>>,
+}
+
+impl LifecycleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the state of a transaction as of `observed_at`. Observations older than the
+    /// last recorded change, and held observations of a settled transaction, are ignored.
+    pub fn observe(&self, transaction: &TransactionResource, observed_at: DateTime<FixedOffset>) {
+        let attributes = &transaction.attributes;
+        let state = match attributes.status {
+            TransactionStatus::Held => LifecycleState::Held,
+            TransactionStatus::Settled => LifecycleState::Settled,
+        };
+
+        let mut transactions = self.lock();
+        let lifecycle = transactions
+            .entry(transaction.id.clone())
+            .or_insert_with(|| TransactionLifecycle {
+                transaction_id: transaction.id.clone(),
+                description: attributes.description.clone(),
+                account_id: transaction.relationships.account.data.id.clone(),
+                created_at: DateTime::parse_from_rfc3339(&attributes.created_at).ok(),
+                history: Vec::new(),
+                held_amount: None,
+                settled_amount: None,
+            });
+
+        // Fetches for webhook events can finish out of order: a stale view must not undo a
+        // later change, and a settled transaction is never held again.
+        let settled = lifecycle.settled_amount.is_some();
+        let stale = lifecycle
+            .history
+            .last()
+            .is_some_and(|change| observed_at < change.observed_at);
+        if stale || (settled && state == LifecycleState::Held) {
+            return;
+        }
+        lifecycle.description = attributes.description.clone();
+
+        match state {
+            LifecycleState::Held => lifecycle.held_amount = Some(attributes.amount.clone()),
+            _ => {
+                if let Some(hold) = &attributes.hold_info {
+                    lifecycle.held_amount = Some(hold.amount.clone());
+                }
+                lifecycle.settled_amount = Some(attributes.amount.clone());
+            }
+        }
+        if lifecycle.state() != Some(state) {
+            lifecycle.history.push(StateChange {
+                state,
+                observed_at,
+                amount: Some(attributes.amount.clone()),
+            });
+        }
+    }
+
+    /// Record that a transaction was deleted. Unknown transactions are ignored.
+    pub fn deleted(&self, transaction_id: &str, observed_at: DateTime<FixedOffset>) {
+        let mut transactions = self.lock();
+        if let Some(lifecycle) = transactions.get_mut(transaction_id)
+            && lifecycle.state() != Some(LifecycleState::Deleted)
+        {
+            lifecycle.history.push(StateChange {
+                state: LifecycleState::Deleted,
+                observed_at,
+                amount: None,
+            });
+        }
+    }
+
+    /// Record a full poll of recent transactions. Held transactions created at or after
+    /// `since` that are missing from `transactions` are treated as deleted.
+    pub fn reconcile(
+        &self,
+        transactions: &[TransactionResource],
+        since: DateTime<FixedOffset>,
+        observed_at: DateTime<FixedOffset>,
+    ) {
+        for transaction in transactions {
+            self.observe(transaction, observed_at);
+        }
+
+        let present: HashSet<&str> = transactions.iter().map(|t| t.id.as_str()).collect();
+        let missing: Vec<String> = self
+            .lock()
+            .values()
+            .filter(|lifecycle| {
+                lifecycle.state() == Some(LifecycleState::Held)
+                    && !present.contains(lifecycle.transaction_id.as_str())
+                    && lifecycle.created_at.is_some_and(|created| created >= since)
+            })
+            .map(|lifecycle| lifecycle.transaction_id.clone())
+            .collect();
+        for transaction_id in missing {
+            self.deleted(&transaction_id, observed_at);
+        }
+    }
+
+    pub fn get(&self, transaction_id: &str) -> Option<TransactionLifecycle> {
+        self.lock().get(transaction_id).cloned()
+    }
+
+    pub fn all(&self) -> Vec<TransactionLifecycle> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Holds that were released without settling.
+    pub fn never_settled(&self) -> Vec<TransactionLifecycle> {
+        self.filtered(TransactionLifecycle::never_settled)
+    }
+
+    /// Transactions that settled for a different amount than was held.
+    pub fn amount_changed(&self) -> Vec<TransactionLifecycle> {
+        self.filtered(|lifecycle| {
+            lifecycle
+                .amount_change_in_base_units()
+                .is_some_and(|c| c != 0)
+        })
+    }
+
+    /// Transactions still held more than `max_age` after they were first seen held.
+    pub fn stale_holds(
+        &self,
+        max_age: TimeDelta,
+        now: DateTime<FixedOffset>,
+    ) -> Vec<TransactionLifecycle> {
+        self.filtered(|lifecycle| {
+            lifecycle
+                .held_since()
+                .is_some_and(|since| now - since > max_age)
+        })
+    }
+
+    fn filtered(
+        &self,
+        predicate: impl Fn(&TransactionLifecycle) -> bool,
+    ) -> Vec<TransactionLifecycle> {
+        self.lock()
+            .values()
+            .filter(|lifecycle| predicate(lifecycle))
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, TransactionLifecycle>> {
+        self.transactions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+type ErrorCallback = dyn Fn(&str, ClientError) + Send + Sync;
+
+/// Feeds `TRANSACTION_CREATED`, `TRANSACTION_SETTLED` and `TRANSACTION_DELETED` webhook
+/// events into a [`LifecycleTracker`].
+///
+/// Created and settled transactions are fetched in the background; deletions are recorded
+/// straight away.
+#[derive(Clone)]
+pub struct LifecycleWebhookHandler {
+    tracker: Arc<LifecycleTracker>,
+    fetcher: TransactionFetcher,
+    on_error: Option<Arc<ErrorCallback>>,
+}
+
+impl fmt::Debug for LifecycleWebhookHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LifecycleWebhookHandler")
+            .field("tracker", &self.tracker)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LifecycleWebhookHandler {
+    /// Transactions are fetched on `runtime`.
+    pub fn new(tracker: Arc<LifecycleTracker>, client: Client, runtime: Handle) -> Self {
+        Self {
+            tracker,
+            fetcher: TransactionFetcher::new(client, runtime),
+            on_error: None,
+        }
+    }
+
+    /// Called when a transaction from a webhook event could not be fetched.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str, ClientError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    fn spawn_observe(&self, transaction_id: &str, event: &WebhookEventResource) {
+        let tracker = Arc::clone(&self.tracker);
+        let on_error = self.on_error.clone();
+        let observed_at = event_time(event);
+
+        self.fetcher.spawn(
+            transaction_id,
+            move |_, transaction_id, result| async move {
+                match result {
+                    Ok(transaction) => tracker.observe(&transaction, observed_at),
+                    Err(e) => {
+                        if let Some(callback) = on_error {
+                            callback(&transaction_id, e);
+                        }
+                    }
+                }
+            },
+        );
+    }
+}
+
+impl WebhookEventHandler for LifecycleWebhookHandler {
+    fn on_transaction_created(&self, transaction_id: &str, event: &WebhookEventResource) {
+        self.spawn_observe(transaction_id, event);
+    }
+
+    fn on_transaction_settled(&self, transaction_id: &str, event: &WebhookEventResource) {
+        self.spawn_observe(transaction_id, event);
+    }
+
+    fn on_transaction_deleted(&self, transaction_id: &str, event: &WebhookEventResource) {
+        self.tracker.deleted(transaction_id, event_time(event));
+    }
+}
+
+fn event_time(event: &WebhookEventResource) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(&event.attributes.created_at)
+        .unwrap_or_else(|_| chrono::Local::now().fixed_offset())
+}
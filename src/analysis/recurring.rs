@@ -0,0 +1,333 @@
+use crate::merchant::MerchantNormalizer;
+use crate::models::transaction::TransactionResource;
+use crate::types::money::MoneyObject;
+use chrono::{DateTime, Days, Months, NaiveDate};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Weekly,
+    Fortnightly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    const ALL: [Frequency; 4] = [
+        Frequency::Weekly,
+        Frequency::Fortnightly,
+        Frequency::Monthly,
+        Frequency::Yearly,
+    ];
+
+    /// Approximate length of one period in days.
+    pub fn days(&self) -> i64 {
+        match self {
+            Frequency::Weekly => 7,
+            Frequency::Fortnightly => 14,
+            Frequency::Monthly => 30,
+            Frequency::Yearly => 365,
+        }
+    }
+
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        let next = match self {
+            Frequency::Weekly => date.checked_add_days(Days::new(7)),
+            Frequency::Fortnightly => date.checked_add_days(Days::new(14)),
+            Frequency::Monthly => date.checked_add_months(Months::new(1)),
+            Frequency::Yearly => date.checked_add_months(Months::new(12)),
+        };
+        next.unwrap_or(NaiveDate::MAX)
+    }
+
+    // Gap lengths, in days, still counted as one period.
+    fn accepts(&self, gap: i64) -> bool {
+        match self {
+            Frequency::Weekly => (6..=8).contains(&gap),
+            Frequency::Fortnightly => (12..=16).contains(&gap),
+            Frequency::Monthly => (27..=34).contains(&gap),
+            Frequency::Yearly => (358..=372).contains(&gap),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceChange {
+    pub previous: MoneyObject,
+    pub current: MoneyObject,
+}
+
+/// A series of transactions with the same merchant, a regular interval and a consistent
+/// amount, such as a subscription, a bill or a salary.
+#[derive(Debug, Clone)]
+pub struct RecurringPayment {
+    /// Canonical merchant name the series was grouped by.
+    pub merchant: String,
+    /// Description of the most recent transaction, for display.
+    pub description: String,
+    /// Account of the most recent transaction.
+    pub account_id: String,
+    pub frequency: Frequency,
+    /// Amount of the most recent transaction; negative for charges, positive for income.
+    pub amount: MoneyObject,
+    pub transaction_ids: Vec<String>,
+    pub last_date: NaiveDate,
+    pub next_expected: NaiveDate,
+    /// Between 0 and 1; how regular the interval and amount have been.
+    pub confidence: f64,
+    /// Set when the latest amount differs from the one before it.
+    pub price_change: Option<PriceChange>,
+}
+
+impl RecurringPayment {
+    pub fn is_income(&self) -> bool {
+        self.amount.value_in_base_units > 0
+    }
+
+    /// Whether a payment was expected but has not arrived for more than half a period,
+    /// suggesting it was cancelled.
+    pub fn is_lapsed(&self, today: NaiveDate) -> bool {
+        (today - self.next_expected).num_days() > self.frequency.days() / 2
+    }
+
+    /// Expected dates of this payment from `from` to `until`, inclusive.
+    pub fn occurrences(&self, from: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        let mut date = self.next_expected;
+        while date <= until {
+            if date >= from {
+                dates.push(date);
+            }
+            if date == NaiveDate::MAX {
+                break;
+            }
+            date = self.frequency.next_after(date);
+        }
+        dates
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UpcomingCharge {
+    pub merchant: String,
+    pub description: String,
+    pub expected_on: NaiveDate,
+    pub amount: MoneyObject,
+    pub confidence: f64,
+    pub price_change: Option<PriceChange>,
+}
+
+/// Detects recurring payments in transaction history.
+///
+/// Transactions are grouped by normalized merchant and direction (charges and income are
+/// separate), then each group is checked for a weekly, fortnightly, monthly or yearly rhythm.
+#[derive(Debug, Clone)]
+pub struct RecurringDetector {
+    min_occurrences: usize,
+    amount_tolerance: f64,
+    min_confidence: f64,
+    include_transfers: bool,
+    normalizer: MerchantNormalizer,
+}
+
+impl Default for RecurringDetector {
+    fn default() -> Self {
+        Self {
+            min_occurrences: 3,
+            amount_tolerance: 0.1,
+            min_confidence: 0.6,
+            include_transfers: false,
+            normalizer: MerchantNormalizer::new(),
+        }
+    }
+}
+
+impl RecurringDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Minimum number of transactions in a series; yearly series need only two.
+    pub fn min_occurrences(mut self, min_occurrences: usize) -> Self {
+        self.min_occurrences = min_occurrences.max(2);
+        self
+    }
+
+    /// Relative difference from the typical amount still counted as the same price.
+    pub fn amount_tolerance(mut self, amount_tolerance: f64) -> Self {
+        self.amount_tolerance = amount_tolerance;
+        self
+    }
+
+    pub fn min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Also detect transfers between Up accounts, such as scheduled savings.
+    pub fn include_transfers(mut self, include_transfers: bool) -> Self {
+        self.include_transfers = include_transfers;
+        self
+    }
+
+    /// Normalizer used to decide which transactions belong to the same merchant.
+    pub fn normalizer(mut self, normalizer: MerchantNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    pub fn detect(&self, transactions: &[TransactionResource]) -> Vec<RecurringPayment> {
+        let mut groups: HashMap<(String, bool), Vec<(NaiveDate, &TransactionResource)>> =
+            HashMap::new();
+
+        for transaction in transactions {
+            if !self.include_transfers && transaction.relationships.transfer_account.data.is_some()
+            {
+                continue;
+            }
+            let Ok(created) = DateTime::parse_from_rfc3339(&transaction.attributes.created_at)
+            else {
+                continue;
+            };
+            let merchant = self.normalizer.merchant(transaction);
+            if merchant.is_empty() {
+                continue;
+            }
+            let income = transaction.attributes.amount.value_in_base_units > 0;
+            groups
+                .entry((merchant.to_lowercase(), income))
+                .or_default()
+                .push((created.date_naive(), transaction));
+        }
+
+        let mut payments: Vec<RecurringPayment> = groups
+            .into_values()
+            .filter_map(|mut series| {
+                series.sort_by_key(|(date, _)| *date);
+                self.analyse(&series)
+            })
+            .filter(|payment| payment.confidence >= self.min_confidence)
+            .collect();
+
+        payments.sort_by_key(|p| p.next_expected);
+        payments
+    }
+
+    /// Charges and income expected between `from` and `from + days`, soonest first.
+    /// Payments that appear to have lapsed by `from` are left out.
+    pub fn upcoming(
+        &self,
+        transactions: &[TransactionResource],
+        from: NaiveDate,
+        days: u64,
+    ) -> Vec<UpcomingCharge> {
+        let until = from
+            .checked_add_days(Days::new(days))
+            .unwrap_or(NaiveDate::MAX);
+
+        let mut upcoming: Vec<UpcomingCharge> = self
+            .detect(transactions)
+            .into_iter()
+            .filter(|payment| !payment.is_lapsed(from))
+            .flat_map(|payment| {
+                payment
+                    .occurrences(from, until)
+                    .into_iter()
+                    .map(move |expected_on| UpcomingCharge {
+                        merchant: payment.merchant.clone(),
+                        description: payment.description.clone(),
+                        expected_on,
+                        amount: payment.amount.clone(),
+                        confidence: payment.confidence,
+                        price_change: payment.price_change.clone(),
+                    })
+            })
+            .collect();
+
+        upcoming.sort_by_key(|charge| charge.expected_on);
+        upcoming
+    }
+
+    fn analyse(&self, series: &[(NaiveDate, &TransactionResource)]) -> Option<RecurringPayment> {
+        // Several charges on one day (e.g. a retry) count as one occurrence.
+        let mut occurrences: Vec<(NaiveDate, &TransactionResource)> = Vec::new();
+        for (date, transaction) in series {
+            match occurrences.last_mut() {
+                Some((last_date, last)) if last_date == date => *last = transaction,
+                _ => occurrences.push((*date, transaction)),
+            }
+        }
+        if occurrences.len() < 2 {
+            return None;
+        }
+
+        let gaps: Vec<i64> = occurrences
+            .windows(2)
+            .map(|pair| (pair[1].0 - pair[0].0).num_days())
+            .collect();
+
+        let (frequency, regularity) = Frequency::ALL
+            .iter()
+            .map(|f| {
+                let matching = gaps.iter().filter(|gap| f.accepts(**gap)).count();
+                (*f, matching as f64 / gaps.len() as f64)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        let required = match frequency {
+            Frequency::Yearly => 2,
+            _ => self.min_occurrences,
+        };
+        if occurrences.len() < required || regularity == 0.0 {
+            return None;
+        }
+
+        let amounts: Vec<i64> = occurrences
+            .iter()
+            .map(|(_, t)| t.attributes.amount.value_in_base_units)
+            .collect();
+        let typical = median(&amounts);
+        let consistent = amounts
+            .iter()
+            .filter(|a| within_tolerance(**a, typical, self.amount_tolerance))
+            .count();
+        let consistency = consistent as f64 / amounts.len() as f64;
+
+        // More occurrences make a pattern more believable, up to six.
+        let support = (occurrences.len() as f64 / 6.0).min(1.0);
+        let confidence = regularity * (0.5 + 0.5 * consistency) * (0.5 + 0.5 * support);
+
+        let (last_date, last) = occurrences[occurrences.len() - 1];
+        let (_, previous) = occurrences[occurrences.len() - 2];
+        let price_change = (last.attributes.amount.value_in_base_units
+            != previous.attributes.amount.value_in_base_units)
+            .then(|| PriceChange {
+                previous: previous.attributes.amount.clone(),
+                current: last.attributes.amount.clone(),
+            });
+
+        Some(RecurringPayment {
+            merchant: self.normalizer.merchant(last),
+            description: last.attributes.description.clone(),
+            account_id: last.relationships.account.data.id.clone(),
+            frequency,
+            amount: last.attributes.amount.clone(),
+            transaction_ids: occurrences.iter().map(|(_, t)| t.id.clone()).collect(),
+            last_date,
+            next_expected: frequency.next_after(last_date),
+            confidence,
+            price_change,
+        })
+    }
+}
+
+fn median(values: &[i64]) -> i64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    sorted[sorted.len() / 2]
+}
+
+fn within_tolerance(amount: i64, typical: i64, tolerance: f64) -> bool {
+    let difference = (amount - typical).unsigned_abs() as f64;
+    difference <= typical.unsigned_abs() as f64 * tolerance
+}
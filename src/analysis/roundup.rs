@@ -0,0 +1,243 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::transaction::TransactionResource;
+use chrono::{DateTime, Datelike, Days, FixedOffset, NaiveDate, TimeDelta};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// First day of the period containing `date`. Weeks start on Monday.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => date
+                .checked_sub_days(Days::new(u64::from(date.weekday().num_days_from_monday())))
+                .unwrap_or(date),
+            Period::Month => date.with_day(1).unwrap_or(date),
+            Period::Year => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Round-ups of one purchase and, when it could be found, the transfer that moved them
+/// into a Saver. Amounts are positive, in base units.
+#[derive(Debug, Clone)]
+pub struct RoundUpMatch {
+    pub purchase_id: String,
+    pub transfer_id: Option<String>,
+    /// The Saver the round-up was transferred into, when the transfer was found.
+    pub saver_account_id: Option<String>,
+    pub date: NaiveDate,
+    /// Total rounded up, including any boost.
+    pub rounded_up: i64,
+    /// The boosted part of `rounded_up`.
+    pub boosted: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundUpPeriodTotal {
+    pub start: NaiveDate,
+    pub purchases: usize,
+    pub rounded_up: i64,
+    pub boosted: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SaverRoundUpTotal {
+    pub account_id: String,
+    pub transfers: usize,
+    pub rounded_up: i64,
+    pub boosted: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundUpReport {
+    pub matches: Vec<RoundUpMatch>,
+    /// Oldest first.
+    pub periods: Vec<RoundUpPeriodTotal>,
+    pub savers: Vec<SaverRoundUpTotal>,
+    /// Round-up transfers into a Saver with no purchase found for them, usually because the
+    /// purchase is outside the transactions analysed.
+    pub unmatched_transfers: Vec<String>,
+}
+
+impl RoundUpReport {
+    pub fn total_rounded_up(&self) -> i64 {
+        self.matches.iter().map(|m| m.rounded_up).sum()
+    }
+
+    pub fn total_boosted(&self) -> i64 {
+        self.matches.iter().map(|m| m.boosted).sum()
+    }
+
+    /// Round-ups whose transfer into a Saver was not found.
+    pub fn unmatched_purchases(&self) -> impl Iterator<Item = &RoundUpMatch> {
+        self.matches.iter().filter(|m| m.transfer_id.is_none())
+    }
+}
+
+/// Totals round-ups and boosts from purchases' `round_up` and matches each to the transfer
+/// that moved it into a Saver.
+///
+/// A transfer matches a purchase when it comes from the purchase's account for exactly the
+/// rounded-up amount within the matching window; the closest in time wins.
+#[derive(Debug, Clone)]
+pub struct RoundUpAnalyzer {
+    period: Period,
+    match_window: TimeDelta,
+}
+
+impl Default for RoundUpAnalyzer {
+    fn default() -> Self {
+        Self {
+            period: Period::Month,
+            match_window: TimeDelta::days(3),
+        }
+    }
+}
+
+impl RoundUpAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn period(mut self, period: Period) -> Self {
+        self.period = period;
+        self
+    }
+
+    /// How far apart a purchase and its round-up transfer may be.
+    pub fn match_window(mut self, match_window: TimeDelta) -> Self {
+        self.match_window = match_window;
+        self
+    }
+
+    pub fn analyze(&self, transactions: &[TransactionResource]) -> RoundUpReport {
+        let mut purchases: Vec<(DateTime<FixedOffset>, &TransactionResource)> = Vec::new();
+        let mut transfers: Vec<(DateTime<FixedOffset>, &TransactionResource)> = Vec::new();
+        for transaction in transactions {
+            let Ok(created) = DateTime::parse_from_rfc3339(&transaction.attributes.created_at)
+            else {
+                continue;
+            };
+            if transaction.attributes.round_up.is_some() {
+                purchases.push((created, transaction));
+            } else if is_round_up_transfer(transaction) {
+                transfers.push((created, transaction));
+            }
+        }
+        purchases.sort_by_key(|(created, _)| *created);
+
+        let mut used = vec![false; transfers.len()];
+        let mut matches = Vec::new();
+        for (created, purchase) in &purchases {
+            let Some(round_up) = &purchase.attributes.round_up else {
+                continue;
+            };
+            let rounded_up = -round_up.amount.value_in_base_units;
+            let boosted = round_up
+                .boost_portion
+                .as_ref()
+                .map_or(0, |boost| -boost.value_in_base_units);
+            let account_id = &purchase.relationships.account.data.id;
+
+            let transfer = transfers
+                .iter()
+                .enumerate()
+                .filter(|(index, (transfer_created, transfer))| {
+                    !used[*index]
+                        && transfer.attributes.amount.value_in_base_units == rounded_up
+                        && transfer
+                            .relationships
+                            .transfer_account
+                            .data
+                            .as_ref()
+                            .is_some_and(|from| from.id == *account_id)
+                        && (*transfer_created - *created).abs() <= self.match_window
+                })
+                .min_by_key(|(_, (transfer_created, _))| (*transfer_created - *created).abs())
+                .map(|(index, (_, transfer))| (index, *transfer));
+            if let Some((index, _)) = transfer {
+                used[index] = true;
+            }
+
+            matches.push(RoundUpMatch {
+                purchase_id: purchase.id.clone(),
+                transfer_id: transfer.map(|(_, t)| t.id.clone()),
+                saver_account_id: transfer.map(|(_, t)| t.relationships.account.data.id.clone()),
+                date: created.date_naive(),
+                rounded_up,
+                boosted,
+            });
+        }
+
+        let unmatched_transfers = transfers
+            .iter()
+            .zip(&used)
+            .filter(|(_, used)| !**used)
+            .map(|((_, transfer), _)| transfer.id.clone())
+            .collect();
+
+        let mut periods: BTreeMap<NaiveDate, RoundUpPeriodTotal> = BTreeMap::new();
+        let mut savers: BTreeMap<&str, SaverRoundUpTotal> = BTreeMap::new();
+        for round_up in &matches {
+            let start = self.period.start_of(round_up.date);
+            let period = periods.entry(start).or_insert(RoundUpPeriodTotal {
+                start,
+                purchases: 0,
+                rounded_up: 0,
+                boosted: 0,
+            });
+            period.purchases += 1;
+            period.rounded_up += round_up.rounded_up;
+            period.boosted += round_up.boosted;
+
+            if let Some(account_id) = &round_up.saver_account_id {
+                let saver = savers
+                    .entry(account_id)
+                    .or_insert_with(|| SaverRoundUpTotal {
+                        account_id: account_id.clone(),
+                        transfers: 0,
+                        rounded_up: 0,
+                        boosted: 0,
+                    });
+                saver.transfers += 1;
+                saver.rounded_up += round_up.rounded_up;
+                saver.boosted += round_up.boosted;
+            }
+        }
+
+        RoundUpReport {
+            periods: periods.into_values().collect(),
+            savers: savers.into_values().collect(),
+            matches,
+            unmatched_transfers,
+        }
+    }
+
+    /// Fetch all transactions since `since` (RFC 3339) and analyse them.
+    pub async fn fetch(&self, client: &Client, since: &str) -> Result<RoundUpReport, ClientError> {
+        let filters = TransactionFilters {
+            since: Some(since.to_string()),
+            ..Default::default()
+        };
+        let transactions = client.list_all_transactions(Some(filters)).await?;
+        Ok(self.analyze(&transactions))
+    }
+}
+
+// Up describes the incoming side of a round-up as "Round Up".
+fn is_round_up_transfer(transaction: &TransactionResource) -> bool {
+    transaction.relationships.transfer_account.data.is_some()
+        && transaction.attributes.amount.value_in_base_units > 0
+        && transaction
+            .attributes
+            .description
+            .to_lowercase()
+            .contains("round up")
+}
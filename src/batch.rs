@@ -0,0 +1,211 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::categories::CategoriesExt;
+use crate::endpoints::tags::TagsExt;
+use crate::endpoints::transactions::TransactionsExt;
+use async_trait::async_trait;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum number of transactions processed at the same time.
+    pub concurrency: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+}
+
+impl BatchOptions {
+    pub fn with_concurrency(concurrency: usize) -> Self {
+        Self { concurrency }
+    }
+}
+
+#[derive(Debug)]
+pub enum BatchOutcome {
+    Succeeded,
+    /// The transaction was left untouched because it cannot be categorized.
+    Skipped,
+    Failed(ClientError),
+}
+
+#[derive(Debug)]
+pub struct BatchItemResult {
+    pub transaction_id: String,
+    pub outcome: BatchOutcome,
+}
+
+/// Per-transaction results of a batch operation, in the order the IDs were given.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchReport {
+    pub fn succeeded(&self) -> impl Iterator<Item = &str> {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, BatchOutcome::Succeeded))
+            .map(|r| r.transaction_id.as_str())
+    }
+
+    pub fn skipped(&self) -> impl Iterator<Item = &str> {
+        self.results
+            .iter()
+            .filter(|r| matches!(r.outcome, BatchOutcome::Skipped))
+            .map(|r| r.transaction_id.as_str())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &ClientError)> {
+        self.results.iter().filter_map(|r| match &r.outcome {
+            BatchOutcome::Failed(e) => Some((r.transaction_id.as_str(), e)),
+            _ => None,
+        })
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.failed().next().is_some()
+    }
+}
+
+#[async_trait]
+pub trait BatchExt {
+    /// Set (or remove, when `category_id` is `None`) the category of every transaction.
+    /// Transactions that are not categorizable are fetched first and reported as skipped.
+    async fn categorize_transactions(
+        &self,
+        transaction_ids: &[&str],
+        category_id: Option<&str>,
+        options: BatchOptions,
+    ) -> BatchReport;
+
+    async fn add_tags_to_transactions(
+        &self,
+        transaction_ids: &[&str],
+        tag_ids: &[&str],
+        options: BatchOptions,
+    ) -> BatchReport;
+
+    async fn remove_tags_from_transactions(
+        &self,
+        transaction_ids: &[&str],
+        tag_ids: &[&str],
+        options: BatchOptions,
+    ) -> BatchReport;
+}
+
+#[async_trait]
+impl BatchExt for Client {
+    async fn categorize_transactions(
+        &self,
+        transaction_ids: &[&str],
+        category_id: Option<&str>,
+        options: BatchOptions,
+    ) -> BatchReport {
+        let category_id = category_id.map(str::to_string);
+
+        run_batch(self, transaction_ids, options, move |client, id| {
+            let category_id = category_id.clone();
+            async move {
+                let transaction = client.get_transaction(&id).await?;
+                if !transaction.data.attributes.is_categorizable {
+                    return Ok(BatchOutcome::Skipped);
+                }
+                client
+                    .categorize_transaction(&id, category_id.as_deref())
+                    .await?;
+                Ok(BatchOutcome::Succeeded)
+            }
+        })
+        .await
+    }
+
+    async fn add_tags_to_transactions(
+        &self,
+        transaction_ids: &[&str],
+        tag_ids: &[&str],
+        options: BatchOptions,
+    ) -> BatchReport {
+        let tag_ids: Arc<Vec<String>> = Arc::new(tag_ids.iter().map(|t| t.to_string()).collect());
+
+        run_batch(self, transaction_ids, options, move |client, id| {
+            let tag_ids = Arc::clone(&tag_ids);
+            async move {
+                let tags = tag_ids.iter().map(String::as_str).collect();
+                client.add_tags_to_transaction(&id, tags).await?;
+                Ok(BatchOutcome::Succeeded)
+            }
+        })
+        .await
+    }
+
+    async fn remove_tags_from_transactions(
+        &self,
+        transaction_ids: &[&str],
+        tag_ids: &[&str],
+        options: BatchOptions,
+    ) -> BatchReport {
+        let tag_ids: Arc<Vec<String>> = Arc::new(tag_ids.iter().map(|t| t.to_string()).collect());
+
+        run_batch(self, transaction_ids, options, move |client, id| {
+            let tag_ids = Arc::clone(&tag_ids);
+            async move {
+                let tags = tag_ids.iter().map(String::as_str).collect();
+                client.remove_tags_from_transaction(&id, tags).await?;
+                Ok(BatchOutcome::Succeeded)
+            }
+        })
+        .await
+    }
+}
+
+pub(crate) async fn run_batch<F, Fut>(
+    client: &Client,
+    transaction_ids: &[&str],
+    options: BatchOptions,
+    operation: F,
+) -> BatchReport
+where
+    F: Fn(Client, String) -> Fut,
+    Fut: Future<Output = Result<BatchOutcome, ClientError>> + Send + 'static,
+{
+    let concurrency = options.concurrency.max(1);
+    let mut pending = transaction_ids.iter().enumerate();
+    let mut tasks = JoinSet::new();
+    let mut outcomes: Vec<Option<BatchOutcome>> = transaction_ids.iter().map(|_| None).collect();
+
+    // Only `concurrency` tasks exist at a time; the next is spawned as one finishes.
+    loop {
+        while tasks.len() < concurrency {
+            let Some((index, id)) = pending.next() else {
+                break;
+            };
+            let future = operation(client.clone(), id.to_string());
+            tasks.spawn(async move { (index, future.await.unwrap_or_else(BatchOutcome::Failed)) });
+        }
+        match tasks.join_next().await {
+            Some(Ok((index, outcome))) => outcomes[index] = Some(outcome),
+            Some(Err(e)) => std::panic::resume_unwind(e.into_panic()),
+            None => break,
+        }
+    }
+
+    let results = transaction_ids
+        .iter()
+        .zip(outcomes)
+        .map(|(id, outcome)| BatchItemResult {
+            transaction_id: id.to_string(),
+            outcome: outcome.expect("every batch task reports an outcome"),
+        })
+        .collect();
+
+    BatchReport { results }
+}
@@ -0,0 +1,470 @@
+mod output;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use output::{Format, Table};
+use std::error::Error;
+use std::path::PathBuf;
+use uprusty::export::TransactionCsvWriter;
+use uprusty::prelude::*;
+use uprusty::{
+    AccountType, AccountsResponse, OwnershipType, TransactionStatus, TransactionsResponse,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "uprusty",
+    version,
+    about = "Command-line client for the Up Banking API"
+)]
+struct Cli {
+    /// Personal access token; defaults to the UP_API_TOKEN environment variable
+    #[arg(long, env = "UP_API_TOKEN", hide_env_values = true, global = true)]
+    token: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value = "table", global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that the token is valid
+    Ping,
+    /// List accounts
+    Accounts {
+        #[arg(long = "type", value_enum)]
+        account_type: Option<AccountTypeArg>,
+        #[arg(long, value_enum)]
+        ownership: Option<OwnershipArg>,
+        #[command(flatten)]
+        paging: Paging,
+    },
+    /// List transactions, optionally for a single account
+    Transactions {
+        /// Only list transactions for this account ID
+        #[arg(long)]
+        account: Option<String>,
+        #[arg(long, value_enum)]
+        status: Option<StatusArg>,
+        /// RFC 3339 timestamp, e.g. 2024-01-01T00:00:00+10:00
+        #[arg(long)]
+        since: Option<String>,
+        /// RFC 3339 timestamp
+        #[arg(long)]
+        until: Option<String>,
+        /// Category ID, e.g. restaurants-and-cafes
+        #[arg(long)]
+        category: Option<String>,
+        /// Tag label
+        #[arg(long)]
+        tag: Option<String>,
+        #[command(flatten)]
+        paging: Paging,
+    },
+    /// Set a transaction's category, or remove it when no category is given
+    Categorize {
+        transaction_id: String,
+        category_id: Option<String>,
+    },
+    /// Add tags to a transaction
+    Tag {
+        transaction_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a transaction
+    Untag {
+        transaction_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Work with attachments
+    #[command(subcommand)]
+    Attachments(AttachmentsCommand),
+    /// Manage webhooks
+    #[command(subcommand)]
+    Webhooks(WebhooksCommand),
+}
+
+#[derive(Subcommand)]
+enum AttachmentsCommand {
+    /// Download an attachment's file
+    Download {
+        id: String,
+        /// Destination file; defaults to <id>.<extension> in the current directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum WebhooksCommand {
+    /// List webhooks
+    List {
+        #[command(flatten)]
+        paging: Paging,
+    },
+    /// Register a new webhook; the secret key is only shown once
+    Create {
+        url: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Delete a webhook
+    Delete { id: String },
+    /// Send a PING event to a webhook
+    Ping { id: String },
+    /// Show delivery logs for a webhook
+    Logs {
+        id: String,
+        #[command(flatten)]
+        paging: Paging,
+    },
+}
+
+#[derive(Args)]
+struct Paging {
+    /// Number of records per page
+    #[arg(long, default_value_t = 100)]
+    page_size: u32,
+    /// Follow pagination links until every record has been fetched
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AccountTypeArg {
+    Saver,
+    Transactional,
+    HomeLoan,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OwnershipArg {
+    Individual,
+    Joint,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatusArg {
+    Held,
+    Settled,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let token = cli
+        .token
+        .ok_or("no token given; pass --token or set UP_API_TOKEN")?;
+    let client = Client::new(&token)?;
+    let format = cli.format;
+
+    match cli.command {
+        Command::Ping => {
+            let ping = client.ping().await?;
+            let mut table = Table::new(vec!["id", "status"]);
+            table.push(vec![
+                json_str(&ping["meta"]["id"]),
+                json_str(&ping["meta"]["statusEmoji"]),
+            ]);
+            output::print(format, &ping, table)?;
+        }
+        Command::Accounts {
+            account_type,
+            ownership,
+            paging,
+        } => {
+            let account_type = account_type.map(|t| match t {
+                AccountTypeArg::Saver => AccountType::Saver,
+                AccountTypeArg::Transactional => AccountType::Transactional,
+                AccountTypeArg::HomeLoan => AccountType::HomeLoan,
+            });
+            let ownership = ownership.map(|o| match o {
+                OwnershipArg::Individual => OwnershipType::Individual,
+                OwnershipArg::Joint => OwnershipType::Joint,
+            });
+
+            let mut accounts = client
+                .list_accounts(Some(paging.page_size), account_type, ownership)
+                .await?;
+            while paging.all
+                && let Some(next) = accounts.links.next.take()
+            {
+                let page: AccountsResponse = client.get_page(&next).await?;
+                accounts.data.extend(page.data);
+                accounts.links = page.links;
+            }
+
+            let mut table = Table::new(vec!["id", "name", "type", "ownership", "balance"]);
+            for account in &accounts.data {
+                let attributes = &account.attributes;
+                table.push(vec![
+                    account.id.clone(),
+                    attributes.display_name.clone(),
+                    format!("{:?}", attributes.account_type),
+                    format!("{:?}", attributes.ownership_type),
+                    money(&attributes.balance),
+                ]);
+            }
+            output::print(format, &accounts.data, table)?;
+        }
+        Command::Transactions {
+            account,
+            status,
+            since,
+            until,
+            category,
+            tag,
+            paging,
+        } => {
+            let filters = TransactionFilters {
+                status: status.map(|s| match s {
+                    StatusArg::Held => TransactionStatus::Held,
+                    StatusArg::Settled => TransactionStatus::Settled,
+                }),
+                since,
+                until,
+                category,
+                tag,
+            };
+
+            let mut transactions = match &account {
+                Some(account_id) => {
+                    client
+                        .list_account_transactions(
+                            account_id,
+                            Some(paging.page_size),
+                            Some(filters),
+                        )
+                        .await?
+                }
+                None => {
+                    client
+                        .list_transactions(Some(paging.page_size), Some(filters))
+                        .await?
+                }
+            };
+            while paging.all
+                && let Some(next) = transactions.links.next.take()
+            {
+                let page: TransactionsResponse = client.get_page(&next).await?;
+                transactions.data.extend(page.data);
+                transactions.links = page.links;
+            }
+
+            // CSV gets the full flattened export rather than the summary table.
+            if let Format::Csv = format {
+                let mut writer = TransactionCsvWriter::new(std::io::stdout().lock());
+                writer.write_all(&transactions.data)?;
+                writer.flush()?;
+                return Ok(());
+            }
+
+            let mut table = Table::new(vec![
+                "id",
+                "created",
+                "status",
+                "description",
+                "amount",
+                "category",
+                "tags",
+            ]);
+            for transaction in &transactions.data {
+                let attributes = &transaction.attributes;
+                let relationships = &transaction.relationships;
+                table.push(vec![
+                    transaction.id.clone(),
+                    attributes.created_at.clone(),
+                    format!("{:?}", attributes.status),
+                    attributes.description.clone(),
+                    money(&attributes.amount),
+                    relationships
+                        .category
+                        .data
+                        .as_ref()
+                        .map(|c| c.id.clone())
+                        .unwrap_or_default(),
+                    relationships
+                        .tags
+                        .data
+                        .iter()
+                        .map(|t| t.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                ]);
+            }
+            output::print(format, &transactions.data, table)?;
+        }
+        Command::Categorize {
+            transaction_id,
+            category_id,
+        } => {
+            client
+                .categorize_transaction(&transaction_id, category_id.as_deref())
+                .await?;
+            match category_id {
+                Some(category) => eprintln!("Categorized {} as {}", transaction_id, category),
+                None => eprintln!("Removed category from {}", transaction_id),
+            }
+        }
+        Command::Tag {
+            transaction_id,
+            tags,
+        } => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            client
+                .add_tags_to_transaction(&transaction_id, tags)
+                .await?;
+            eprintln!("Tagged {}", transaction_id);
+        }
+        Command::Untag {
+            transaction_id,
+            tags,
+        } => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            client
+                .remove_tags_from_transaction(&transaction_id, tags)
+                .await?;
+            eprintln!("Untagged {}", transaction_id);
+        }
+        Command::Attachments(AttachmentsCommand::Download { id, output }) => {
+            let attachment = client.get_attachment(&id).await?.data;
+            let path = output.unwrap_or_else(|| {
+                let extension = attachment
+                    .attributes
+                    .file_extension
+                    .as_deref()
+                    .map(|ext| ext.trim_start_matches('.'))
+                    .unwrap_or("bin");
+                PathBuf::from(format!("{}.{}", id, extension))
+            });
+            // Download to a temporary name so a failed download leaves no file behind.
+            let mut partial_path = path.clone().into_os_string();
+            partial_path.push(".part");
+            let mut file = tokio::fs::File::create(&partial_path).await?;
+            let downloaded = client
+                .download_attachment_resource(&attachment, &mut file)
+                .await;
+            drop(file);
+            let bytes = match downloaded {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(&partial_path).await;
+                    return Err(e.into());
+                }
+            };
+            tokio::fs::rename(&partial_path, &path).await?;
+            eprintln!("Wrote {} bytes to {}", bytes, path.display());
+        }
+        Command::Webhooks(command) => webhooks(&client, format, command).await?,
+    }
+
+    Ok(())
+}
+
+async fn webhooks(
+    client: &Client,
+    format: Format,
+    command: WebhooksCommand,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        WebhooksCommand::List { paging } => {
+            let mut webhooks = client.list_webhooks(Some(paging.page_size)).await?;
+            while paging.all
+                && let Some(next) = webhooks.links.next.take()
+            {
+                let page: uprusty::WebhooksResponse = client.get_page(&next).await?;
+                webhooks.data.extend(page.data);
+                webhooks.links = page.links;
+            }
+
+            let mut table = Table::new(vec!["id", "url", "description", "created"]);
+            for webhook in &webhooks.data {
+                let attributes = &webhook.attributes;
+                table.push(vec![
+                    webhook.id.clone(),
+                    attributes.url.clone(),
+                    attributes.description.clone().unwrap_or_default(),
+                    attributes.created_at.clone(),
+                ]);
+            }
+            output::print(format, &webhooks.data, table)?;
+        }
+        WebhooksCommand::Create { url, description } => {
+            let webhook = client
+                .create_webhook(&url, description.as_deref())
+                .await?
+                .data;
+            let mut table = Table::new(vec!["id", "url", "secret_key"]);
+            table.push(vec![
+                webhook.id.clone(),
+                webhook.attributes.url.clone(),
+                webhook.attributes.secret_key.clone().unwrap_or_default(),
+            ]);
+            output::print(format, &webhook, table)?;
+        }
+        WebhooksCommand::Delete { id } => {
+            client.delete_webhook(&id).await?;
+            eprintln!("Deleted webhook {}", id);
+        }
+        WebhooksCommand::Ping { id } => {
+            let event = client.ping_webhook(&id).await?.data;
+            let mut table = Table::new(vec!["event_id", "type", "created"]);
+            table.push(vec![
+                event.id.clone(),
+                format!("{:?}", event.attributes.event_type),
+                event.attributes.created_at.clone(),
+            ]);
+            output::print(format, &event, table)?;
+        }
+        WebhooksCommand::Logs { id, paging } => {
+            let mut logs = client
+                .list_webhook_logs(&id, Some(paging.page_size))
+                .await?;
+            while paging.all
+                && let Some(next) = logs.links.next.take()
+            {
+                let page: uprusty::WebhookDeliveryLogsResponse = client.get_page(&next).await?;
+                logs.data.extend(page.data);
+                logs.links = page.links;
+            }
+
+            let mut table = Table::new(vec!["id", "status", "response_code", "created"]);
+            for log in &logs.data {
+                let attributes = &log.attributes;
+                table.push(vec![
+                    log.id.clone(),
+                    format!("{:?}", attributes.delivery_status),
+                    attributes
+                        .response
+                        .as_ref()
+                        .map(|r| r.status_code.to_string())
+                        .unwrap_or_default(),
+                    attributes.created_at.clone(),
+                ]);
+            }
+            output::print(format, &logs.data, table)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn money(amount: &MoneyObject) -> String {
+    format!("{} {}", amount.value, amount.currency_code)
+}
+
+fn json_str(value: &serde_json::Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
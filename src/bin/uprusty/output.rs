@@ -0,0 +1,72 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::error::Error;
+use uprusty::export::csv_row;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Rows of text printed either as an aligned table or as CSV.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    fn print_aligned(&self) {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            println!("{}", padded.join("  ").trim_end());
+        };
+
+        line(self.headers.clone());
+        for row in &self.rows {
+            line(row.iter().map(String::as_str).collect());
+        }
+    }
+
+    fn print_csv(&self) {
+        print!("{}", csv_row(self.headers.iter().copied()));
+        for row in &self.rows {
+            print!("{}", csv_row(row.iter().map(String::as_str)));
+        }
+    }
+}
+
+/// Print `value` as JSON, or `table` in the requested text format.
+pub fn print<T: Serialize>(format: Format, value: &T, table: Table) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Table => table.print_aligned(),
+        Format::Csv => table.print_csv(),
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        }
+    }
+    Ok(())
+}
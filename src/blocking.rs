@@ -0,0 +1,306 @@
+//! A synchronous client for code that does not run inside an async runtime.
+//!
+//! Every method blocks the calling thread on the matching async endpoint, using a private
+//! single-threaded Tokio runtime. The blocking client must not be used from within an
+//! async context, where blocking would stall (or panic) the surrounding runtime.
+
+use crate::client::{self, ClientError};
+use crate::endpoints::accounts::AccountsExt;
+use crate::endpoints::attachments::AttachmentsExt;
+use crate::endpoints::categories::CategoriesExt;
+use crate::endpoints::tags::{TagChanges, TagsExt};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::endpoints::webhooks::WebhooksExt;
+use crate::models::account::{AccountResponse, AccountType, AccountsResponse, OwnershipType};
+use crate::models::attachment::{AttachmentResource, AttachmentResponse, AttachmentsResponse};
+use crate::models::category::{CategoriesResponse, CategoryResponse};
+use crate::models::tags::TagsResponse;
+use crate::models::transaction::{TransactionResource, TransactionResponse, TransactionsResponse};
+use crate::models::webhooks::{
+    WebhookDeliveryLogsResponse, WebhookEventResponse, WebhookResponse, WebhooksResponse,
+};
+use crate::transport::Transport;
+use crate::types::traits::RelatedLink;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+use tokio::runtime::{Builder, Runtime};
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: client::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    pub fn new(token: &str) -> Result<Self, ClientError> {
+        Self::from_async(client::Client::new(token)?)
+    }
+
+    /// Send requests through `transport` instead of the default reqwest client.
+    pub fn with_transport(
+        token: &str,
+        transport: impl Transport + 'static,
+    ) -> Result<Self, ClientError> {
+        Self::from_async(client::Client::with_transport(token, transport)?)
+    }
+
+    /// Wrap an existing async client.
+    pub fn from_async(inner: client::Client) -> Result<Self, ClientError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The async client this one wraps.
+    pub fn as_async(&self) -> &client::Client {
+        &self.inner
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    pub fn ping(&self) -> Result<serde_json::Value, ClientError> {
+        self.block_on(self.inner.ping())
+    }
+
+    pub fn get_related<T, L>(&self, link: &L) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        L: RelatedLink + ?Sized,
+    {
+        self.block_on(self.inner.get_related(link))
+    }
+
+    pub fn get_page<T: DeserializeOwned>(&self, link: &str) -> Result<T, ClientError> {
+        self.block_on(self.inner.get_page(link))
+    }
+
+    // Accounts
+
+    pub fn list_accounts(
+        &self,
+        page_size: Option<u32>,
+        account_type: Option<AccountType>,
+        ownership_type: Option<OwnershipType>,
+    ) -> Result<AccountsResponse, ClientError> {
+        self.block_on(
+            self.inner
+                .list_accounts(page_size, account_type, ownership_type),
+        )
+    }
+
+    pub fn get_account(&self, id: &str) -> Result<AccountResponse, ClientError> {
+        self.block_on(self.inner.get_account(id))
+    }
+
+    // Attachments
+
+    pub fn list_attachments(
+        &self,
+        page_size: Option<u32>,
+    ) -> Result<AttachmentsResponse, ClientError> {
+        self.block_on(self.inner.list_attachments(page_size))
+    }
+
+    pub fn get_attachment(&self, id: &str) -> Result<AttachmentResponse, ClientError> {
+        self.block_on(self.inner.get_attachment(id))
+    }
+
+    /// Write the file of an attachment to `writer`, returning the number of bytes written.
+    pub fn download_attachment<W>(&self, id: &str, writer: &mut W) -> Result<u64, ClientError>
+    where
+        W: io::Write + Send + ?Sized,
+    {
+        let mut writer = SyncWriter(writer);
+        self.block_on(self.inner.download_attachment(id, &mut writer))
+    }
+
+    pub fn download_attachment_resource<W>(
+        &self,
+        attachment: &AttachmentResource,
+        writer: &mut W,
+    ) -> Result<u64, ClientError>
+    where
+        W: io::Write + Send + ?Sized,
+    {
+        let mut writer = SyncWriter(writer);
+        self.block_on(
+            self.inner
+                .download_attachment_resource(attachment, &mut writer),
+        )
+    }
+
+    // Categories
+
+    pub fn list_categories(
+        &self,
+        parent_filter: Option<&str>,
+    ) -> Result<CategoriesResponse, ClientError> {
+        self.block_on(self.inner.list_categories(parent_filter))
+    }
+
+    pub fn get_category(&self, id: &str) -> Result<CategoryResponse, ClientError> {
+        self.block_on(self.inner.get_category(id))
+    }
+
+    pub fn categorize_transaction(
+        &self,
+        transaction_id: &str,
+        category_id: Option<&str>,
+    ) -> Result<(), ClientError> {
+        self.block_on(
+            self.inner
+                .categorize_transaction(transaction_id, category_id),
+        )
+    }
+
+    // Tags
+
+    pub fn list_tags(&self, page_size: Option<u32>) -> Result<TagsResponse, ClientError> {
+        self.block_on(self.inner.list_tags(page_size))
+    }
+
+    pub fn tag_transactions(
+        &self,
+        tag_id: &str,
+        page_size: Option<u32>,
+        filters: Option<TransactionFilters>,
+    ) -> Result<TransactionsResponse, ClientError> {
+        self.block_on(self.inner.tag_transactions(tag_id, page_size, filters))
+    }
+
+    pub fn add_tags_to_transaction(
+        &self,
+        transaction_id: &str,
+        tag_ids: Vec<&str>,
+    ) -> Result<(), ClientError> {
+        self.block_on(self.inner.add_tags_to_transaction(transaction_id, tag_ids))
+    }
+
+    pub fn remove_tags_from_transaction(
+        &self,
+        transaction_id: &str,
+        tag_ids: Vec<&str>,
+    ) -> Result<(), ClientError> {
+        self.block_on(
+            self.inner
+                .remove_tags_from_transaction(transaction_id, tag_ids),
+        )
+    }
+
+    pub fn set_transaction_tags(
+        &self,
+        transaction_id: &str,
+        desired: Vec<&str>,
+    ) -> Result<TagChanges, ClientError> {
+        self.block_on(self.inner.set_transaction_tags(transaction_id, desired))
+    }
+
+    // Transactions
+
+    pub fn list_transactions(
+        &self,
+        page_size: Option<u32>,
+        filters: Option<TransactionFilters>,
+    ) -> Result<TransactionsResponse, ClientError> {
+        self.block_on(self.inner.list_transactions(page_size, filters))
+    }
+
+    pub fn get_transaction(&self, id: &str) -> Result<TransactionResponse, ClientError> {
+        self.block_on(self.inner.get_transaction(id))
+    }
+
+    pub fn list_account_transactions(
+        &self,
+        account_id: &str,
+        page_size: Option<u32>,
+        filters: Option<TransactionFilters>,
+    ) -> Result<TransactionsResponse, ClientError> {
+        self.block_on(
+            self.inner
+                .list_account_transactions(account_id, page_size, filters),
+        )
+    }
+
+    pub fn list_all_transactions(
+        &self,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError> {
+        self.block_on(self.inner.list_all_transactions(filters))
+    }
+
+    pub fn list_all_account_transactions(
+        &self,
+        account_id: &str,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError> {
+        self.block_on(
+            self.inner
+                .list_all_account_transactions(account_id, filters),
+        )
+    }
+
+    // Webhooks
+
+    pub fn list_webhooks(&self, page_size: Option<u32>) -> Result<WebhooksResponse, ClientError> {
+        self.block_on(self.inner.list_webhooks(page_size))
+    }
+
+    pub fn create_webhook(
+        &self,
+        url: &str,
+        description: Option<&str>,
+    ) -> Result<WebhookResponse, ClientError> {
+        self.block_on(self.inner.create_webhook(url, description))
+    }
+
+    pub fn get_webhook(&self, id: &str) -> Result<WebhookResponse, ClientError> {
+        self.block_on(self.inner.get_webhook(id))
+    }
+
+    pub fn delete_webhook(&self, id: &str) -> Result<(), ClientError> {
+        self.block_on(self.inner.delete_webhook(id))
+    }
+
+    pub fn ping_webhook(&self, id: &str) -> Result<WebhookEventResponse, ClientError> {
+        self.block_on(self.inner.ping_webhook(id))
+    }
+
+    pub fn list_webhook_logs(
+        &self,
+        webhook_id: &str,
+        page_size: Option<u32>,
+    ) -> Result<WebhookDeliveryLogsResponse, ClientError> {
+        self.block_on(self.inner.list_webhook_logs(webhook_id, page_size))
+    }
+}
+
+// Lets the async download write straight into a std writer. Writes complete immediately,
+// which is fine on the blocking client's own runtime thread.
+struct SyncWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: io::Write + ?Sized> AsyncWrite for SyncWriter<'_, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
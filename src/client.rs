@@ -1,10 +1,15 @@
+use crate::transport::{HttpResponse, RequestBuilder, ReqwestTransport, Transport};
+use crate::types::traits::RelatedLink;
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
-use reqwest::{Client as HttpClient, Method, RequestBuilder};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
 use url::ParseError;
 
 #[derive(Clone, Debug)]
 pub struct Client {
-    pub(crate) http: HttpClient,
+    pub(crate) transport: Arc<dyn Transport>,
     pub(crate) base_url: url::Url,
     pub(crate) token: String,
 }
@@ -14,8 +19,64 @@ pub enum ClientError {
     ParseError(ParseError),
     InvalidToken,
     RequestError(reqwest::Error),
+    /// The API answered with a 4xx or 5xx status.
+    HttpStatus {
+        status: StatusCode,
+        body: String,
+    },
+    Json(serde_json::Error),
+    /// An error from a custom [`Transport`].
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    TooManyTags {
+        count: usize,
+        limit: usize,
+    },
+    InvalidTag(String),
+    MissingRelatedLink,
+    ForeignLink(String),
+    MissingFileUrl,
+    ContentTypeMismatch {
+        expected: String,
+        actual: Option<String>,
+    },
+    Io(std::io::Error),
 }
 
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::ParseError(e) => write!(f, "URL parse error: {}", e),
+            ClientError::InvalidToken => write!(f, "Invalid API token"),
+            ClientError::RequestError(e) => write!(f, "Request error: {}", e),
+            ClientError::HttpStatus { status, body } => {
+                write!(f, "HTTP status {}: {}", status, body)
+            }
+            ClientError::Json(e) => write!(f, "JSON error: {}", e),
+            ClientError::Transport(e) => write!(f, "Transport error: {}", e),
+            ClientError::TooManyTags { count, limit } => write!(
+                f,
+                "Too many tags: {} requested, at most {} allowed per transaction",
+                count, limit
+            ),
+            ClientError::InvalidTag(tag) => write!(f, "Invalid tag: {:?}", tag),
+            ClientError::MissingRelatedLink => write!(f, "Relationship has no related link"),
+            ClientError::ForeignLink(link) => {
+                write!(f, "Refusing to follow link outside the Up API: {}", link)
+            }
+            ClientError::MissingFileUrl => write!(f, "Attachment has no file URL"),
+            ClientError::ContentTypeMismatch { expected, actual } => write!(
+                f,
+                "Expected content type {}, got {}",
+                expected,
+                actual.as_deref().unwrap_or("none")
+            ),
+            ClientError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
 impl From<ParseError> for ClientError {
     fn from(err: ParseError) -> Self {
         ClientError::ParseError(err)
@@ -28,14 +89,34 @@ impl From<reqwest::Error> for ClientError {
     }
 }
 
+impl From<serde_json::Error> for ClientError {
+    fn from(err: serde_json::Error) -> Self {
+        ClientError::Json(err)
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(err: std::io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
 impl Client {
     pub fn new(token: &str) -> Result<Self, ClientError> {
+        Self::with_transport(token, ReqwestTransport::new())
+    }
+
+    /// Send requests through `transport` instead of the default reqwest client.
+    pub fn with_transport(
+        token: &str,
+        transport: impl Transport + 'static,
+    ) -> Result<Self, ClientError> {
         if token.is_empty() || !token.starts_with("up:yeah:") {
             return Err(ClientError::InvalidToken);
         }
 
         Ok(Self {
-            http: HttpClient::new(),
+            transport: Arc::new(transport),
             base_url: url::Url::parse("https://api.up.com.au/api/v1/")?,
             token: token.to_string(),
         })
@@ -44,14 +125,39 @@ impl Client {
     //Utility function to verify auth is ok [https://developer.up.com.au/#get_util_ping]
     pub async fn ping(&self) -> Result<serde_json::Value, ClientError> {
         let url = self.base_url.join("util/ping")?;
-        let headers = self.auth_headers()?;
-
-        let response = self.http.get(url).headers(headers).send().await?;
+        let response = self.request(Method::GET, url)?.send().await?;
+        let response = response.error_for_status().await?;
 
         let json = response.json::<serde_json::Value>().await?;
         Ok(json)
     }
 
+    /// Fetch the resource(s) a relationship link points at, e.g. the transactions of a tag
+    /// via `tag.relationships.transactions.links`. The response type is chosen by the caller.
+    pub async fn get_related<T, L>(&self, link: &L) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        L: RelatedLink + ?Sized,
+    {
+        let related = link.related().ok_or(ClientError::MissingRelatedLink)?;
+        self.get_page(related).await
+    }
+
+    /// Fetch an absolute API URL, such as the `next` or `prev` link of a paginated response.
+    pub async fn get_page<T: DeserializeOwned>(&self, link: &str) -> Result<T, ClientError> {
+        let url = url::Url::parse(link)?;
+
+        // Never send the bearer token anywhere but the Up API.
+        if url.origin() != self.base_url.origin() {
+            return Err(ClientError::ForeignLink(link.to_string()));
+        }
+
+        let response = self.request(Method::GET, url)?.send().await?;
+        let response = response.error_for_status().await?;
+        let page = response.json::<T>().await?;
+        Ok(page)
+    }
+
     //Request builder with authentication
     pub(crate) fn request(
         &self,
@@ -59,21 +165,23 @@ impl Client {
         url: url::Url,
     ) -> Result<RequestBuilder, ClientError> {
         let headers = self.auth_headers()?;
-        Ok(self.http.request(method, url).headers(headers))
+        Ok(self.unauthenticated(method, url).headers(headers))
+    }
+
+    //Request builder without the bearer token, for URLs outside the API
+    pub(crate) fn unauthenticated(&self, method: Method, url: url::Url) -> RequestBuilder {
+        RequestBuilder::new(Arc::clone(&self.transport), method, url)
     }
 
     /// Helper method to handle responses that should return 204 No Content
     pub(crate) async fn handle_no_content_response(
         &self,
-        response: reqwest::Response,
+        response: HttpResponse,
     ) -> Result<(), ClientError> {
         match response.status() {
-            reqwest::StatusCode::NO_CONTENT => Ok(()),
+            StatusCode::NO_CONTENT => Ok(()),
             _ => {
-                response
-                    .error_for_status()
-                    .map(|_| ()) // Convert success response to ()
-                    .map_err(ClientError::RequestError)
+                response.error_for_status().await.map(|_| ()) // Convert success response to ()
             }
         }
     }
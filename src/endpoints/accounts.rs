@@ -51,9 +51,7 @@ impl AccountsExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let accounts = response.json::<AccountsResponse>().await?;
         Ok(accounts)
     }
@@ -62,9 +60,7 @@ impl AccountsExt for Client {
         let url = self.base_url.join(&format!("accounts/{}", id))?;
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let account = response.json::<AccountResponse>().await?;
         Ok(account)
     }
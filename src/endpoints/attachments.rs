@@ -1,7 +1,13 @@
 use crate::client::{Client, ClientError};
-use crate::models::attachment::{AttachmentResponse, AttachmentsResponse};
+use crate::models::attachment::{AttachmentResource, AttachmentResponse, AttachmentsResponse};
+use crate::transport::HttpResponse;
 use async_trait::async_trait;
-use reqwest::Method;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{Method, StatusCode};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Treat file URLs this close to expiry as already expired.
+const EXPIRY_MARGIN_SECONDS: i64 = 30;
 
 #[async_trait]
 pub trait AttachmentsExt {
@@ -14,6 +20,21 @@ pub trait AttachmentsExt {
 
     /// Retrieve a specific attachment by providing its unique identifier.
     async fn get_attachment(&self, id: &str) -> Result<AttachmentResponse, ClientError>;
+
+    /// Stream the file of an attachment to `writer`, returning the number of bytes written.
+    async fn download_attachment<W>(&self, id: &str, writer: &mut W) -> Result<u64, ClientError>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized;
+
+    /// Like `download_attachment`, but starts from an already fetched attachment. The
+    /// attachment is re-fetched if its pre-signed file URL has expired or is rejected.
+    async fn download_attachment_resource<W>(
+        &self,
+        attachment: &AttachmentResource,
+        writer: &mut W,
+    ) -> Result<u64, ClientError>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized;
 }
 
 #[async_trait]
@@ -30,9 +51,7 @@ impl AttachmentsExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let attachments = response.json::<AttachmentsResponse>().await?;
         Ok(attachments)
     }
@@ -41,10 +60,108 @@ impl AttachmentsExt for Client {
         let url = self.base_url.join(&format!("attachments/{}", id))?;
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let attachment = response.json::<AttachmentResponse>().await?;
         Ok(attachment)
     }
+
+    async fn download_attachment<W>(&self, id: &str, writer: &mut W) -> Result<u64, ClientError>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        let attachment = self.get_attachment(id).await?;
+        self.download_attachment_resource(&attachment.data, writer)
+            .await
+    }
+
+    async fn download_attachment_resource<W>(
+        &self,
+        attachment: &AttachmentResource,
+        writer: &mut W,
+    ) -> Result<u64, ClientError>
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        let mut attachment = attachment.clone();
+        if file_url_expired(&attachment) {
+            attachment = self.get_attachment(&attachment.id).await?.data;
+        }
+
+        let mut response = fetch_file(self, &attachment).await?;
+        if response.status() == StatusCode::FORBIDDEN {
+            attachment = self.get_attachment(&attachment.id).await?.data;
+            response = fetch_file(self, &attachment).await?;
+        }
+
+        let mut response = response.error_for_status().await?;
+
+        if let Some(expected) = &attachment.attributes.file_content_type {
+            let actual = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            if !content_type_matches(expected, actual.as_deref()) {
+                return Err(ClientError::ContentTypeMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        let mut written = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+}
+
+// The file URL is pre-signed, so it must be fetched without the API bearer token.
+async fn fetch_file(
+    client: &Client,
+    attachment: &AttachmentResource,
+) -> Result<HttpResponse, ClientError> {
+    let file_url = attachment
+        .attributes
+        .file_url
+        .as_deref()
+        .ok_or(ClientError::MissingFileUrl)?;
+    let url = url::Url::parse(file_url)?;
+    client.unauthenticated(Method::GET, url).send().await
+}
+
+fn file_url_expired(attachment: &AttachmentResource) -> bool {
+    if attachment.attributes.file_url.is_none() {
+        return true;
+    }
+
+    let Some(expires_at) = &attachment.attributes.file_url_expires_at else {
+        return false;
+    };
+    match DateTime::parse_from_rfc3339(expires_at) {
+        Ok(expires_at) => {
+            expires_at.with_timezone(&Utc) <= Utc::now() + Duration::seconds(EXPIRY_MARGIN_SECONDS)
+        }
+        Err(_) => false,
+    }
+}
+
+fn content_type_matches(expected: &str, actual: Option<&str>) -> bool {
+    let essence = |value: &str| {
+        value
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+
+    match actual {
+        Some(actual) => essence(expected) == essence(actual),
+        None => false,
+    }
 }
@@ -32,9 +32,7 @@ impl CategoriesExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let categories = response.json::<CategoriesResponse>().await?;
         Ok(categories)
     }
@@ -43,9 +41,7 @@ impl CategoriesExt for Client {
         let url = self.base_url.join(&format!("categories/{}", id))?;
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let category = response.json::<CategoryResponse>().await?;
         Ok(category)
     }
@@ -1,12 +1,58 @@
 use crate::client::{Client, ClientError};
-use crate::models::tags::{TagsResponse, TagsTransactionRequest};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::tags::{
+    MAX_TAG_LENGTH, MAX_TAGS_PER_TRANSACTION, TagsResponse, TagsTransactionRequest,
+};
+use crate::models::transaction::TransactionsResponse;
 use async_trait::async_trait;
 use reqwest::Method;
 
+/// The tags added to and removed from a transaction by `set_transaction_tags`.
+#[derive(Debug, Clone, Default)]
+pub struct TagChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl TagChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Check tag labels against Up's per-transaction limit and length constraints.
+pub fn validate_tags(tag_ids: &[&str]) -> Result<(), ClientError> {
+    if tag_ids.len() > MAX_TAGS_PER_TRANSACTION {
+        return Err(ClientError::TooManyTags {
+            count: tag_ids.len(),
+            limit: MAX_TAGS_PER_TRANSACTION,
+        });
+    }
+
+    for tag in tag_ids {
+        if tag.trim().is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(ClientError::InvalidTag(tag.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 pub trait TagsExt {
     async fn list_tags(&self, page_size: Option<u32>) -> Result<TagsResponse, ClientError>;
 
+    /// List the transactions carrying a tag. Any `tag` already set in `filters` is replaced.
+    async fn tag_transactions(
+        &self,
+        tag_id: &str,
+        page_size: Option<u32>,
+        filters: Option<TransactionFilters>,
+    ) -> Result<TransactionsResponse, ClientError>;
+
+    /// Add tags to a transaction. Only `tag_ids` is checked against
+    /// `MAX_TAGS_PER_TRANSACTION`; the tags the transaction already has are not, so the
+    /// API may still reject the request. `set_transaction_tags` checks the full set.
     async fn add_tags_to_transaction(
         &self,
         transaction_id: &str,
@@ -18,6 +64,15 @@ pub trait TagsExt {
         transaction_id: &str,
         tag_ids: Vec<&str>,
     ) -> Result<(), ClientError>;
+
+    /// Make the transaction's tags exactly `desired`, issuing only the DELETE and POST
+    /// calls needed to get there from its current tags. Fails with `TooManyTags` before
+    /// any request if `desired` is over the limit.
+    async fn set_transaction_tags(
+        &self,
+        transaction_id: &str,
+        desired: Vec<&str>,
+    ) -> Result<TagChanges, ClientError>;
 }
 
 #[async_trait]
@@ -31,13 +86,24 @@ impl TagsExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let tags = response.json::<TagsResponse>().await?;
         Ok(tags)
     }
 
+    async fn tag_transactions(
+        &self,
+        tag_id: &str,
+        page_size: Option<u32>,
+        filters: Option<TransactionFilters>,
+    ) -> Result<TransactionsResponse, ClientError> {
+        let filters = TransactionFilters {
+            tag: Some(tag_id.to_string()),
+            ..filters.unwrap_or_default()
+        };
+        self.list_transactions(page_size, Some(filters)).await
+    }
+
     async fn add_tags_to_transaction(
         &self,
         transaction_id: &str,
@@ -48,6 +114,7 @@ impl TagsExt for Client {
             transaction_id
         ))?;
 
+        validate_tags(&tag_ids)?;
         let body = TagsTransactionRequest::new(tag_ids);
 
         let response = self.request(Method::POST, url)?.json(&body).send().await?;
@@ -75,4 +142,55 @@ impl TagsExt for Client {
 
         self.handle_no_content_response(response).await
     }
+
+    async fn set_transaction_tags(
+        &self,
+        transaction_id: &str,
+        desired: Vec<&str>,
+    ) -> Result<TagChanges, ClientError> {
+        let mut desired_tags: Vec<&str> = Vec::new();
+        for tag in desired {
+            if !desired_tags.contains(&tag) {
+                desired_tags.push(tag);
+            }
+        }
+        validate_tags(&desired_tags)?;
+
+        let transaction = self.get_transaction(transaction_id).await?;
+        let current: Vec<String> = transaction
+            .data
+            .relationships
+            .tags
+            .data
+            .into_iter()
+            .map(|tag| tag.id)
+            .collect();
+
+        let changes = TagChanges {
+            added: desired_tags
+                .iter()
+                .filter(|tag| !current.iter().any(|c| c == *tag))
+                .map(|tag| tag.to_string())
+                .collect(),
+            removed: current
+                .iter()
+                .filter(|c| !desired_tags.contains(&c.as_str()))
+                .cloned()
+                .collect(),
+        };
+
+        // Remove first so the transaction never exceeds the tag limit mid-update.
+        if !changes.removed.is_empty() {
+            let tags = changes.removed.iter().map(String::as_str).collect();
+            self.remove_tags_from_transaction(transaction_id, tags)
+                .await?;
+        }
+
+        if !changes.added.is_empty() {
+            let tags = changes.added.iter().map(String::as_str).collect();
+            self.add_tags_to_transaction(transaction_id, tags).await?;
+        }
+
+        Ok(changes)
+    }
 }
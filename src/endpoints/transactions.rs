@@ -1,9 +1,11 @@
 use crate::client::{Client, ClientError};
-use crate::models::transaction::{TransactionResponse, TransactionStatus, TransactionsResponse};
+use crate::models::transaction::{
+    TransactionResource, TransactionResponse, TransactionStatus, TransactionsResponse,
+};
 use async_trait::async_trait;
 use reqwest::Method;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TransactionFilters {
     pub status: Option<TransactionStatus>,
     pub since: Option<String>,
@@ -12,18 +14,6 @@ pub struct TransactionFilters {
     pub tag: Option<String>,
 }
 
-impl Default for TransactionFilters {
-    fn default() -> Self {
-        Self {
-            status: None,
-            since: None,
-            until: None,
-            category: None,
-            tag: None,
-        }
-    }
-}
-
 #[async_trait]
 pub trait TransactionsExt {
     async fn list_transactions(
@@ -40,8 +30,23 @@ pub trait TransactionsExt {
         page_size: Option<u32>,
         filters: Option<TransactionFilters>,
     ) -> Result<TransactionsResponse, ClientError>;
+
+    /// Fetch every page of `list_transactions`.
+    async fn list_all_transactions(
+        &self,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError>;
+
+    /// Fetch every page of `list_account_transactions`.
+    async fn list_all_account_transactions(
+        &self,
+        account_id: &str,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError>;
 }
 
+const MAX_PAGE_SIZE: u32 = 100;
+
 #[async_trait]
 impl TransactionsExt for Client {
     async fn list_transactions(
@@ -86,9 +91,7 @@ impl TransactionsExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let transactions = response.json::<TransactionsResponse>().await?;
         Ok(transactions)
     }
@@ -97,9 +100,7 @@ impl TransactionsExt for Client {
         let url = self.base_url.join(&format!("transactions/{}", id))?;
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let transaction = response.json::<TransactionResponse>().await?;
         Ok(transaction)
     }
@@ -149,10 +150,41 @@ impl TransactionsExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let transactions = response.json::<TransactionsResponse>().await?;
         Ok(transactions)
     }
+
+    async fn list_all_transactions(
+        &self,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError> {
+        let first = self.list_transactions(Some(MAX_PAGE_SIZE), filters).await?;
+        collect_pages(self, first).await
+    }
+
+    async fn list_all_account_transactions(
+        &self,
+        account_id: &str,
+        filters: Option<TransactionFilters>,
+    ) -> Result<Vec<TransactionResource>, ClientError> {
+        let first = self
+            .list_account_transactions(account_id, Some(MAX_PAGE_SIZE), filters)
+            .await?;
+        collect_pages(self, first).await
+    }
+}
+
+async fn collect_pages(
+    client: &Client,
+    mut page: TransactionsResponse,
+) -> Result<Vec<TransactionResource>, ClientError> {
+    let mut transactions = Vec::new();
+    loop {
+        transactions.append(&mut page.data);
+        match page.links.next.take() {
+            Some(next) => page = client.get_page(&next).await?,
+            None => return Ok(transactions),
+        }
+    }
 }
@@ -40,9 +40,7 @@ impl WebhooksExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let webhooks = response.json::<WebhooksResponse>().await?;
         Ok(webhooks)
     }
@@ -61,9 +59,7 @@ impl WebhooksExt for Client {
             .send()
             .await?;
 
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let webhook = response.json::<WebhookResponse>().await?;
         Ok(webhook)
     }
@@ -72,9 +68,7 @@ impl WebhooksExt for Client {
         let url = self.base_url.join(&format!("webhooks/{}", id))?;
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let webhook = response.json::<WebhookResponse>().await?;
         Ok(webhook)
     }
@@ -96,9 +90,7 @@ impl WebhooksExt for Client {
             .send()
             .await?;
 
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let event = response.json::<WebhookEventResponse>().await?;
         Ok(event)
     }
@@ -118,9 +110,7 @@ impl WebhooksExt for Client {
         }
 
         let response = self.request(Method::GET, url)?.send().await?;
-        let response = response
-            .error_for_status()
-            .map_err(ClientError::RequestError)?;
+        let response = response.error_for_status().await?;
         let logs = response.json::<WebhookDeliveryLogsResponse>().await?;
         Ok(logs)
     }
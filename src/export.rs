@@ -0,0 +1,47 @@
+pub mod csv;
+pub mod ledger;
+pub mod ofx;
+pub mod qif;
+pub mod receipts;
+
+pub use csv::{TransactionColumn, TransactionCsvWriter};
+pub use ledger::{LedgerExporter, LedgerFormat};
+pub use ofx::OfxExporter;
+pub use qif::QifExporter;
+pub use receipts::{ReceiptArchive, ReceiptArchiveSummary};
+
+use crate::models::transaction::TransactionResource;
+use std::borrow::Cow;
+
+/// The timestamp a transaction took effect: when it settled, or when it was created if it
+/// is still held.
+pub(crate) fn posted_at(transaction: &TransactionResource) -> &str {
+    let attributes = &transaction.attributes;
+    attributes
+        .settled_at
+        .as_deref()
+        .unwrap_or(&attributes.created_at)
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Join fields into one CSV line, ending with a newline.
+pub fn csv_row<'a, I>(fields: I) -> String
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut row = fields
+        .into_iter()
+        .map(csv_field)
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
@@ -0,0 +1,220 @@
+use crate::export::csv_row;
+use crate::models::transaction::TransactionResource;
+use crate::types::money::MoneyObject;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// A column of the flattened transaction CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionColumn {
+    Id,
+    Account,
+    Status,
+    CreatedAt,
+    SettledAt,
+    Description,
+    RawText,
+    Message,
+    Amount,
+    Currency,
+    ForeignAmount,
+    ForeignCurrency,
+    Category,
+    ParentCategory,
+    Tags,
+    CardMethod,
+    CardSuffix,
+    RoundUp,
+    RoundUpBoost,
+    Cashback,
+    Note,
+}
+
+impl TransactionColumn {
+    pub const ALL: [TransactionColumn; 21] = [
+        TransactionColumn::Id,
+        TransactionColumn::Account,
+        TransactionColumn::Status,
+        TransactionColumn::CreatedAt,
+        TransactionColumn::SettledAt,
+        TransactionColumn::Description,
+        TransactionColumn::RawText,
+        TransactionColumn::Message,
+        TransactionColumn::Amount,
+        TransactionColumn::Currency,
+        TransactionColumn::ForeignAmount,
+        TransactionColumn::ForeignCurrency,
+        TransactionColumn::Category,
+        TransactionColumn::ParentCategory,
+        TransactionColumn::Tags,
+        TransactionColumn::CardMethod,
+        TransactionColumn::CardSuffix,
+        TransactionColumn::RoundUp,
+        TransactionColumn::RoundUpBoost,
+        TransactionColumn::Cashback,
+        TransactionColumn::Note,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            TransactionColumn::Id => "id",
+            TransactionColumn::Account => "account",
+            TransactionColumn::Status => "status",
+            TransactionColumn::CreatedAt => "created_at",
+            TransactionColumn::SettledAt => "settled_at",
+            TransactionColumn::Description => "description",
+            TransactionColumn::RawText => "raw_text",
+            TransactionColumn::Message => "message",
+            TransactionColumn::Amount => "amount",
+            TransactionColumn::Currency => "currency",
+            TransactionColumn::ForeignAmount => "foreign_amount",
+            TransactionColumn::ForeignCurrency => "foreign_currency",
+            TransactionColumn::Category => "category",
+            TransactionColumn::ParentCategory => "parent_category",
+            TransactionColumn::Tags => "tags",
+            TransactionColumn::CardMethod => "card_method",
+            TransactionColumn::CardSuffix => "card_suffix",
+            TransactionColumn::RoundUp => "round_up",
+            TransactionColumn::RoundUpBoost => "round_up_boost",
+            TransactionColumn::Cashback => "cashback",
+            TransactionColumn::Note => "note",
+        }
+    }
+
+    pub fn value(&self, transaction: &TransactionResource) -> String {
+        let attributes = &transaction.attributes;
+        let relationships = &transaction.relationships;
+        let value = |money: Option<&MoneyObject>| money.map(|m| m.value.clone());
+
+        match self {
+            TransactionColumn::Id => Some(transaction.id.clone()),
+            TransactionColumn::Account => Some(relationships.account.data.id.clone()),
+            TransactionColumn::Status => Some(serialized_name(&attributes.status)),
+            TransactionColumn::CreatedAt => Some(attributes.created_at.clone()),
+            TransactionColumn::SettledAt => attributes.settled_at.clone(),
+            TransactionColumn::Description => Some(attributes.description.clone()),
+            TransactionColumn::RawText => attributes.raw_text.clone(),
+            TransactionColumn::Message => attributes.message.clone(),
+            TransactionColumn::Amount => Some(attributes.amount.value.clone()),
+            TransactionColumn::Currency => Some(attributes.amount.currency_code.clone()),
+            TransactionColumn::ForeignAmount => value(attributes.foreign_amount.as_ref()),
+            TransactionColumn::ForeignCurrency => attributes
+                .foreign_amount
+                .as_ref()
+                .map(|m| m.currency_code.clone()),
+            TransactionColumn::Category => {
+                relationships.category.data.as_ref().map(|c| c.id.clone())
+            }
+            TransactionColumn::ParentCategory => relationships
+                .parent_category
+                .data
+                .as_ref()
+                .map(|c| c.id.clone()),
+            TransactionColumn::Tags => Some(
+                relationships
+                    .tags
+                    .data
+                    .iter()
+                    .map(|t| t.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ),
+            TransactionColumn::CardMethod => attributes
+                .card_purchase_method
+                .as_ref()
+                .map(|c| serialized_name(&c.method)),
+            TransactionColumn::CardSuffix => attributes
+                .card_purchase_method
+                .as_ref()
+                .and_then(|c| c.card_number_suffix.clone()),
+            TransactionColumn::RoundUp => value(attributes.round_up.as_ref().map(|r| &r.amount)),
+            TransactionColumn::RoundUpBoost => value(
+                attributes
+                    .round_up
+                    .as_ref()
+                    .and_then(|r| r.boost_portion.as_ref()),
+            ),
+            TransactionColumn::Cashback => value(attributes.cashback.as_ref().map(|c| &c.amount)),
+            TransactionColumn::Note => attributes.note.as_ref().map(|n| n.text.clone()),
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// Writes transactions as CSV rows, one per transaction, with a header row first.
+///
+/// ```no_run
+/// # async fn run(client: uprusty::Client) -> Result<(), Box<dyn std::error::Error>> {
+/// use uprusty::TransactionsExt;
+/// use uprusty::export::TransactionCsvWriter;
+///
+/// let transactions = client.list_transactions(Some(100), None).await?;
+/// let mut writer = TransactionCsvWriter::new(std::fs::File::create("transactions.csv")?);
+/// writer.write_all(&transactions.data)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TransactionCsvWriter<W: Write> {
+    writer: W,
+    columns: Vec<TransactionColumn>,
+    header_written: bool,
+}
+
+impl<W: Write> TransactionCsvWriter<W> {
+    /// A writer emitting every column in `TransactionColumn::ALL` order.
+    pub fn new(writer: W) -> Self {
+        Self::with_columns(writer, TransactionColumn::ALL.to_vec())
+    }
+
+    pub fn with_columns(writer: W, columns: Vec<TransactionColumn>) -> Self {
+        Self {
+            writer,
+            columns,
+            header_written: false,
+        }
+    }
+
+    pub fn write(&mut self, transaction: &TransactionResource) -> io::Result<()> {
+        self.write_header()?;
+        let values: Vec<String> = self.columns.iter().map(|c| c.value(transaction)).collect();
+        self.writer
+            .write_all(csv_row(values.iter().map(String::as_str)).as_bytes())
+    }
+
+    pub fn write_all<'a, I>(&mut self, transactions: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a TransactionResource>,
+    {
+        // An empty export still gets its header row.
+        self.write_header()?;
+        for transaction in transactions {
+            self.write(transaction)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if !self.header_written {
+            self.header_written = true;
+            let row = csv_row(self.columns.iter().map(TransactionColumn::header));
+            self.writer.write_all(row.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// Upper-case API name of an enum value, e.g. `CARD_ON_FILE`.
+fn serialized_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
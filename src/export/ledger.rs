@@ -0,0 +1,499 @@
+use crate::export::posted_at;
+use crate::models::account::{AccountResource, AccountType};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::types::money::MoneyObject;
+use chrono::{DateTime, Days, FixedOffset, Local, NaiveDate};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerFormat {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+/// Writes Up accounts and transactions as a plain-text accounting journal.
+///
+/// Every Up account gets an opening balance entry so that, once all of its exported
+/// transactions are applied, it ends at the account's current `balance`; a balance
+/// assertion checks this when the journal is loaded. Transfers between two exported
+/// accounts are written once, as a single two-leg entry.
+#[derive(Debug, Clone)]
+pub struct LedgerExporter {
+    format: LedgerFormat,
+    accounts: HashMap<String, String>,
+    categories: HashMap<String, String>,
+    asset_root: String,
+    liability_root: String,
+    expense_root: String,
+    uncategorized_account: String,
+    income_account: String,
+    opening_balance_account: String,
+}
+
+impl LedgerExporter {
+    pub fn new(format: LedgerFormat) -> Self {
+        Self {
+            format,
+            accounts: HashMap::new(),
+            categories: HashMap::new(),
+            asset_root: "Assets:Up".to_string(),
+            liability_root: "Liabilities:Up".to_string(),
+            expense_root: "Expenses".to_string(),
+            uncategorized_account: "Expenses:Uncategorized".to_string(),
+            income_account: "Income:Up".to_string(),
+            opening_balance_account: "Equity:Opening-Balances".to_string(),
+        }
+    }
+
+    /// Use `name` for the Up account with this ID instead of one derived from its display name.
+    pub fn account(mut self, account_id: &str, name: &str) -> Self {
+        self.accounts
+            .insert(account_id.to_string(), name.to_string());
+        self
+    }
+
+    /// Post transactions in this category (or parent category) to `name`. Unmapped
+    /// categories are posted to `<expense root>:<Parent>:<Category>`.
+    pub fn category(mut self, category_id: &str, name: &str) -> Self {
+        self.categories
+            .insert(category_id.to_string(), name.to_string());
+        self
+    }
+
+    pub fn asset_root(mut self, name: &str) -> Self {
+        self.asset_root = name.to_string();
+        self
+    }
+
+    pub fn liability_root(mut self, name: &str) -> Self {
+        self.liability_root = name.to_string();
+        self
+    }
+
+    pub fn expense_root(mut self, name: &str) -> Self {
+        self.expense_root = name.to_string();
+        self
+    }
+
+    pub fn uncategorized_account(mut self, name: &str) -> Self {
+        self.uncategorized_account = name.to_string();
+        self
+    }
+
+    pub fn income_account(mut self, name: &str) -> Self {
+        self.income_account = name.to_string();
+        self
+    }
+
+    pub fn opening_balance_account(mut self, name: &str) -> Self {
+        self.opening_balance_account = name.to_string();
+        self
+    }
+
+    pub fn write<'t, W, I>(
+        &self,
+        mut writer: W,
+        accounts: &[AccountResource],
+        transactions: I,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = &'t TransactionResource>,
+    {
+        let names: HashMap<&str, String> = accounts
+            .iter()
+            .map(|a| (a.id.as_str(), self.account_name(a)))
+            .collect();
+
+        let mut dated = Vec::new();
+        for transaction in transactions {
+            if names.contains_key(transaction.relationships.account.data.id.as_str()) {
+                dated.push((parse_timestamp(posted_at(transaction))?, transaction));
+            }
+        }
+        dated.sort_by_key(|(posted, _)| *posted);
+
+        let today = Local::now().date_naive();
+        let first_date = dated.first().map_or(today, |(d, _)| d.date_naive());
+        let last_date = dated.last().map_or(today, |(d, _)| d.date_naive());
+
+        let mut entries = Vec::new();
+
+        let mut unmatched_transfers: Vec<&TransactionResource> = dated
+            .iter()
+            .map(|(_, t)| *t)
+            .filter(|t| {
+                t.attributes.amount.value_in_base_units < 0
+                    && t.relationships
+                        .transfer_account
+                        .data
+                        .as_ref()
+                        .is_some_and(|to| names.contains_key(to.id.as_str()))
+            })
+            .collect();
+
+        for (posted, transaction) in &dated {
+            let attributes = &transaction.attributes;
+            let account_id = transaction.relationships.account.data.id.as_str();
+            let amount = &attributes.amount;
+
+            // The incoming half of a transfer whose outgoing half is also exported.
+            if let Some(from) = &transaction.relationships.transfer_account.data
+                && amount.value_in_base_units > 0
+            {
+                let outgoing = unmatched_transfers.iter().position(|out| {
+                    out.relationships.account.data.id == from.id
+                        && out
+                            .relationships
+                            .transfer_account
+                            .data
+                            .as_ref()
+                            .is_some_and(|to| to.id == account_id)
+                        && out.attributes.amount.value_in_base_units == -amount.value_in_base_units
+                });
+                if let Some(index) = outgoing {
+                    unmatched_transfers.remove(index);
+                    continue;
+                }
+            }
+
+            let counter = self.counter_posting(transaction, &names);
+            entries.push(Entry {
+                date: posted.date_naive(),
+                pending: matches!(attributes.status, TransactionStatus::Held),
+                payee: attributes.description.clone(),
+                narration: attributes
+                    .message
+                    .clone()
+                    .or_else(|| attributes.raw_text.clone()),
+                id: Some(transaction.id.clone()),
+                tags: transaction
+                    .relationships
+                    .tags
+                    .data
+                    .iter()
+                    .map(|t| t.id.clone())
+                    .collect(),
+                postings: vec![
+                    Posting::new(&names[account_id], amount, amount.value_in_base_units),
+                    counter,
+                ],
+            });
+        }
+
+        // Opening balances make each account end at its current balance. The movement is
+        // taken from the postings written, which include transfers from exported accounts
+        // whose incoming half is missing.
+        let mut opening_entries = Vec::new();
+        for account in accounts {
+            let balance = &account.attributes.balance;
+            let name = &names[account.id.as_str()];
+            let moved: i64 = entries
+                .iter()
+                .flat_map(|e| &e.postings)
+                .filter(|p| &p.account == name && p.amount.currency_code == balance.currency_code)
+                .map(|p| p.amount.value_in_base_units)
+                .sum();
+            let opening = balance.value_in_base_units - moved;
+            if opening != 0 {
+                opening_entries.push(Entry {
+                    date: first_date,
+                    pending: false,
+                    payee: "Opening balance".to_string(),
+                    narration: None,
+                    id: None,
+                    tags: Vec::new(),
+                    postings: vec![
+                        Posting::new(name, balance, opening),
+                        Posting::new(&self.opening_balance_account, balance, -opening),
+                    ],
+                });
+            }
+        }
+        entries.splice(0..0, opening_entries);
+
+        match self.format {
+            LedgerFormat::Beancount => self.write_beancount(
+                &mut writer,
+                accounts,
+                &names,
+                &entries,
+                first_date,
+                last_date,
+            ),
+            LedgerFormat::Ledger | LedgerFormat::Hledger => {
+                self.write_ledger(&mut writer, accounts, &names, &entries, last_date)
+            }
+        }
+    }
+
+    fn account_name(&self, account: &AccountResource) -> String {
+        if let Some(name) = self.accounts.get(&account.id) {
+            return name.clone();
+        }
+
+        let root = match account.attributes.account_type {
+            AccountType::HomeLoan => &self.liability_root,
+            AccountType::Saver | AccountType::Transactional => &self.asset_root,
+        };
+        let component = account_component(&account.attributes.display_name);
+        if component.is_empty() {
+            format!("{}:{}", root, account_component(&account.id))
+        } else {
+            format!("{}:{}", root, component)
+        }
+    }
+
+    fn counter_posting(
+        &self,
+        transaction: &TransactionResource,
+        names: &HashMap<&str, String>,
+    ) -> Posting {
+        let attributes = &transaction.attributes;
+        let relationships = &transaction.relationships;
+        let amount = &attributes.amount;
+
+        if let Some(other) = &relationships.transfer_account.data {
+            let name = names
+                .get(other.id.as_str())
+                .cloned()
+                .or_else(|| self.accounts.get(&other.id).cloned())
+                .unwrap_or_else(|| format!("{}:{}", self.asset_root, account_component(&other.id)));
+            return Posting::new(&name, amount, -amount.value_in_base_units);
+        }
+
+        let category = relationships.category.data.as_ref().map(|c| c.id.as_str());
+        let parent = relationships
+            .parent_category
+            .data
+            .as_ref()
+            .map(|c| c.id.as_str());
+        let account = match (category, parent) {
+            (Some(category), parent) => self
+                .categories
+                .get(category)
+                .or_else(|| parent.and_then(|p| self.categories.get(p)))
+                .cloned()
+                .unwrap_or_else(|| {
+                    let mut name = self.expense_root.clone();
+                    for id in parent.into_iter().chain([category]) {
+                        name.push(':');
+                        name.push_str(&account_component(id));
+                    }
+                    name
+                }),
+            (None, _) if amount.value_in_base_units > 0 => self.income_account.clone(),
+            (None, _) => self.uncategorized_account.clone(),
+        };
+
+        match &attributes.foreign_amount {
+            Some(foreign) => Posting {
+                account,
+                amount: foreign.with_base_units(-foreign.value_in_base_units),
+                price: Some(amount.with_base_units(amount.value_in_base_units.abs())),
+            },
+            None => Posting::new(&account, amount, -amount.value_in_base_units),
+        }
+    }
+
+    fn write_beancount<W: Write>(
+        &self,
+        writer: &mut W,
+        accounts: &[AccountResource],
+        names: &HashMap<&str, String>,
+        entries: &[Entry],
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+    ) -> io::Result<()> {
+        let mut opened: Vec<&str> = Vec::new();
+        for posting in entries.iter().flat_map(|e| &e.postings) {
+            if !opened.contains(&posting.account.as_str()) {
+                opened.push(&posting.account);
+            }
+        }
+        for account in accounts {
+            let name = names[account.id.as_str()].as_str();
+            if !opened.contains(&name) {
+                opened.push(name);
+            }
+        }
+        for account in &opened {
+            writeln!(writer, "{} open {}", first_date, account)?;
+        }
+        writeln!(writer)?;
+
+        for entry in entries {
+            write!(
+                writer,
+                "{} {} \"{}\" \"{}\"",
+                entry.date,
+                if entry.pending { '!' } else { '*' },
+                quote(&entry.payee),
+                quote(entry.narration.as_deref().unwrap_or_default())
+            )?;
+            for tag in &entry.tags {
+                write!(writer, " #{}", tag_name(tag))?;
+            }
+            writeln!(writer)?;
+            if let Some(id) = &entry.id {
+                writeln!(writer, "  up_id: \"{}\"", quote(id))?;
+            }
+            for posting in &entry.postings {
+                writeln!(writer, "  {}", posting)?;
+            }
+            writeln!(writer)?;
+        }
+
+        // Balance assertions apply at the start of their day, so check the day after.
+        let check_date = last_date + Days::new(1);
+        for account in accounts {
+            let balance = &account.attributes.balance;
+            writeln!(
+                writer,
+                "{} balance {} {} {}",
+                check_date,
+                names[account.id.as_str()],
+                balance.value,
+                balance.currency_code
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_ledger<W: Write>(
+        &self,
+        writer: &mut W,
+        accounts: &[AccountResource],
+        names: &HashMap<&str, String>,
+        entries: &[Entry],
+        last_date: NaiveDate,
+    ) -> io::Result<()> {
+        for entry in entries {
+            writeln!(
+                writer,
+                "{} {} {}",
+                entry.date,
+                if entry.pending { '!' } else { '*' },
+                single_line(&entry.payee)
+            )?;
+            if let Some(narration) = &entry.narration {
+                writeln!(writer, "    ; {}", single_line(narration))?;
+            }
+            if let Some(id) = &entry.id {
+                writeln!(writer, "    ; up-id: {}", id)?;
+            }
+            for tag in &entry.tags {
+                match self.format {
+                    LedgerFormat::Hledger => writeln!(writer, "    ; {}:", tag_name(tag))?,
+                    _ => writeln!(writer, "    ; :{}:", tag_name(tag))?,
+                }
+            }
+            for posting in &entry.postings {
+                writeln!(writer, "    {}", posting)?;
+            }
+            writeln!(writer)?;
+        }
+
+        for account in accounts {
+            let balance = &account.attributes.balance;
+            writeln!(writer, "{} * Balance check", last_date)?;
+            writeln!(
+                writer,
+                "    {}  0 {} = {} {}",
+                names[account.id.as_str()],
+                balance.currency_code,
+                balance.value,
+                balance.currency_code
+            )?;
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Entry {
+    date: NaiveDate,
+    pending: bool,
+    payee: String,
+    narration: Option<String>,
+    id: Option<String>,
+    tags: Vec<String>,
+    postings: Vec<Posting>,
+}
+
+struct Posting {
+    account: String,
+    amount: MoneyObject,
+    /// Total cost of `amount` in the account's currency, for foreign currency postings.
+    price: Option<MoneyObject>,
+}
+
+impl Posting {
+    fn new(account: &str, currency: &MoneyObject, value_in_base_units: i64) -> Self {
+        Self {
+            account: account.to_string(),
+            amount: currency.with_base_units(value_in_base_units),
+            price: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Posting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}  {} {}",
+            self.account, self.amount.value, self.amount.currency_code
+        )?;
+        if let Some(price) = &self.price {
+            write!(f, " @@ {} {}", price.value, price.currency_code)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> io::Result<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Turns display names and IDs into an account name component valid in all three formats,
+// e.g. "🏖️ Holiday fund" becomes "Holiday-Fund".
+fn account_component(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn quote(value: &str) -> String {
+    single_line(value)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
@@ -0,0 +1,209 @@
+use crate::export::posted_at;
+use crate::models::account::{AccountResource, AccountType};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use chrono::{DateTime, FixedOffset, Local};
+use std::io::{self, Write};
+
+/// Up's BSB, used as the OFX bank identifier.
+const UP_BANK_ID: &str = "633123";
+const MAX_ACCOUNT_ID_LENGTH: usize = 22;
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_MEMO_LENGTH: usize = 255;
+
+/// Writes an account and its transactions as an OFX 2.x bank statement.
+///
+/// Transaction IDs are used as `FITID`s, so re-importing an overlapping statement does not
+/// duplicate entries. Held transactions are left out unless `include_held` is set, since
+/// their amount can still change when they settle.
+///
+/// OFX limits `ACCTID` to 22 characters, so by default it is the start of the account's
+/// UUID without hyphens.
+#[derive(Debug, Clone)]
+pub struct OfxExporter<'a> {
+    account: &'a AccountResource,
+    account_id: Option<String>,
+    include_held: bool,
+}
+
+impl<'a> OfxExporter<'a> {
+    pub fn new(account: &'a AccountResource) -> Self {
+        Self {
+            account,
+            account_id: None,
+            include_held: false,
+        }
+    }
+
+    /// Use `account_id` as the `ACCTID`, e.g. to match an account already set up in the
+    /// importing tool. It is cut to 22 characters.
+    pub fn account_id(mut self, account_id: &str) -> Self {
+        self.account_id = Some(truncate(account_id, MAX_ACCOUNT_ID_LENGTH));
+        self
+    }
+
+    pub fn include_held(mut self, include_held: bool) -> Self {
+        self.include_held = include_held;
+        self
+    }
+
+    pub fn write<'t, W, I>(&self, mut writer: W, transactions: I) -> io::Result<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = &'t TransactionResource>,
+    {
+        let account = self.account;
+        let now = ofx_datetime(&Local::now().fixed_offset());
+
+        let mut entries = String::new();
+        let mut start: Option<DateTime<FixedOffset>> = None;
+        let mut end: Option<DateTime<FixedOffset>> = None;
+
+        for transaction in transactions {
+            if transaction.relationships.account.data.id != account.id {
+                continue;
+            }
+            if matches!(transaction.attributes.status, TransactionStatus::Held)
+                && !self.include_held
+            {
+                continue;
+            }
+
+            let posted = parse_timestamp(posted_at(transaction))?;
+            start = Some(start.map_or(posted, |s| s.min(posted)));
+            end = Some(end.map_or(posted, |e| e.max(posted)));
+            entries.push_str(&statement_transaction(transaction, &posted)?);
+        }
+
+        let start = start
+            .map(|d| ofx_datetime(&d))
+            .unwrap_or_else(|| now.clone());
+        let end = end.map(|d| ofx_datetime(&d)).unwrap_or_else(|| now.clone());
+        let attributes = &account.attributes;
+        let account_type = match attributes.account_type {
+            AccountType::Transactional => "CHECKING",
+            AccountType::Saver => "SAVINGS",
+            AccountType::HomeLoan => "CREDITLINE",
+        };
+
+        write!(
+            writer,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+             <?OFX OFXHEADER=\"200\" VERSION=\"211\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n\
+             <OFX>\n\
+             <SIGNONMSGSRSV1><SONRS>\n\
+             <STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n\
+             <DTSERVER>{now}</DTSERVER>\n\
+             <LANGUAGE>ENG</LANGUAGE>\n\
+             </SONRS></SIGNONMSGSRSV1>\n\
+             <BANKMSGSRSV1><STMTTRNRS>\n\
+             <TRNUID>0</TRNUID>\n\
+             <STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n\
+             <STMTRS>\n\
+             <CURDEF>{currency}</CURDEF>\n\
+             <BANKACCTFROM><BANKID>{bank_id}</BANKID><ACCTID>{account_id}</ACCTID><ACCTTYPE>{account_type}</ACCTTYPE></BANKACCTFROM>\n\
+             <BANKTRANLIST>\n\
+             <DTSTART>{start}</DTSTART>\n\
+             <DTEND>{end}</DTEND>\n\
+             {entries}\
+             </BANKTRANLIST>\n\
+             <LEDGERBAL><BALAMT>{balance}</BALAMT><DTASOF>{now}</DTASOF></LEDGERBAL>\n\
+             </STMTRS>\n\
+             </STMTTRNRS></BANKMSGSRSV1>\n\
+             </OFX>\n",
+            currency = escape(&attributes.balance.currency_code),
+            bank_id = UP_BANK_ID,
+            account_id = escape(&self.ofx_account_id()),
+            balance = escape(&attributes.balance.value),
+        )
+    }
+
+    fn ofx_account_id(&self) -> String {
+        match &self.account_id {
+            Some(account_id) => account_id.clone(),
+            None => self
+                .account
+                .id
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .take(MAX_ACCOUNT_ID_LENGTH)
+                .collect(),
+        }
+    }
+}
+
+fn statement_transaction(
+    transaction: &TransactionResource,
+    posted: &DateTime<FixedOffset>,
+) -> io::Result<String> {
+    let attributes = &transaction.attributes;
+    let created = parse_timestamp(&attributes.created_at)?;
+
+    let transaction_type = if transaction.relationships.transfer_account.data.is_some() {
+        "XFER"
+    } else if attributes.amount.value_in_base_units < 0 {
+        "DEBIT"
+    } else {
+        "CREDIT"
+    };
+
+    let mut entry = format!(
+        "<STMTTRN>\n\
+         <TRNTYPE>{}</TRNTYPE>\n\
+         <DTPOSTED>{}</DTPOSTED>\n\
+         <DTUSER>{}</DTUSER>\n\
+         <TRNAMT>{}</TRNAMT>\n\
+         <FITID>{}</FITID>\n\
+         <NAME>{}</NAME>\n",
+        transaction_type,
+        ofx_datetime(posted),
+        ofx_datetime(&created),
+        escape(&attributes.amount.value),
+        escape(&transaction.id),
+        escape(&truncate(&attributes.description, MAX_NAME_LENGTH)),
+    );
+
+    let memo = attributes
+        .message
+        .as_deref()
+        .or(attributes.raw_text.as_deref())
+        .filter(|memo| !memo.is_empty());
+    if let Some(memo) = memo {
+        entry.push_str(&format!(
+            "<MEMO>{}</MEMO>\n",
+            escape(&truncate(memo, MAX_MEMO_LENGTH))
+        ));
+    }
+    entry.push_str("</STMTTRN>\n");
+
+    Ok(entry)
+}
+
+fn parse_timestamp(timestamp: &str) -> io::Result<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// OFX datetimes look like `20240301123045.000[+10:AEST]`; the zone name is optional.
+fn ofx_datetime(datetime: &DateTime<FixedOffset>) -> String {
+    let offset_minutes = datetime.offset().local_minus_utc() / 60;
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (offset_minutes.abs() / 60, offset_minutes.abs() % 60);
+    let offset = if minutes == 0 {
+        format!("{}{}", sign, hours)
+    } else {
+        format!("{}{}.{:02}", sign, hours, minutes * 100 / 60)
+    };
+
+    format!("{}[{}]", datetime.format("%Y%m%d%H%M%S%.3f"), offset)
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    value.chars().take(max_chars).collect()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
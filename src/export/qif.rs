@@ -0,0 +1,109 @@
+use crate::export::posted_at;
+use crate::models::account::{AccountResource, AccountType};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use chrono::DateTime;
+use std::io::{self, Write};
+
+/// Writes an account and its transactions as a QIF document.
+///
+/// Dates are written day-first (`31/12/2024`) by default, matching how Up reports them;
+/// use `date_format` with a `chrono` format string for tools that expect another layout.
+#[derive(Debug, Clone)]
+pub struct QifExporter<'a> {
+    account: &'a AccountResource,
+    include_held: bool,
+    date_format: String,
+}
+
+impl<'a> QifExporter<'a> {
+    pub fn new(account: &'a AccountResource) -> Self {
+        Self {
+            account,
+            include_held: false,
+            date_format: "%d/%m/%Y".to_string(),
+        }
+    }
+
+    pub fn include_held(mut self, include_held: bool) -> Self {
+        self.include_held = include_held;
+        self
+    }
+
+    pub fn date_format(mut self, date_format: &str) -> Self {
+        self.date_format = date_format.to_string();
+        self
+    }
+
+    pub fn write<'t, W, I>(&self, mut writer: W, transactions: I) -> io::Result<()>
+    where
+        W: Write,
+        I: IntoIterator<Item = &'t TransactionResource>,
+    {
+        let account = self.account;
+        let account_type = match account.attributes.account_type {
+            AccountType::Transactional | AccountType::Saver => "Bank",
+            AccountType::HomeLoan => "Oth L",
+        };
+
+        write!(
+            writer,
+            "!Account\nN{}\nT{}\n^\n!Type:{}\n",
+            single_line(&account.attributes.display_name),
+            account_type,
+            account_type
+        )?;
+
+        for transaction in transactions {
+            if transaction.relationships.account.data.id != account.id {
+                continue;
+            }
+            if matches!(transaction.attributes.status, TransactionStatus::Held)
+                && !self.include_held
+            {
+                continue;
+            }
+            self.write_transaction(&mut writer, transaction)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_transaction<W: Write>(
+        &self,
+        writer: &mut W,
+        transaction: &TransactionResource,
+    ) -> io::Result<()> {
+        let attributes = &transaction.attributes;
+        let posted = DateTime::parse_from_rfc3339(posted_at(transaction))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        writeln!(writer, "D{}", posted.format(&self.date_format))?;
+        writeln!(writer, "T{}", attributes.amount.value)?;
+        writeln!(writer, "P{}", single_line(&attributes.description))?;
+
+        let memo = attributes
+            .message
+            .as_deref()
+            .or(attributes.raw_text.as_deref())
+            .filter(|memo| !memo.is_empty());
+        if let Some(memo) = memo {
+            writeln!(writer, "M{}", single_line(memo))?;
+        }
+
+        if let Some(category) = &transaction.relationships.category.data {
+            writeln!(writer, "L{}", category.id)?;
+        }
+
+        // Cleared, not reconciled: reconciling is done in the accounting tool.
+        if matches!(attributes.status, TransactionStatus::Settled) {
+            writeln!(writer, "C*")?;
+        }
+
+        writeln!(writer, "^")
+    }
+}
+
+// QIF is line-oriented, so fields must not contain line breaks.
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
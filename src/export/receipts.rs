@@ -0,0 +1,207 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::attachments::AttachmentsExt;
+use crate::endpoints::transactions::TransactionsExt;
+use crate::export::{csv_row, posted_at};
+use crate::models::attachment::{AttachmentResource, AttachmentsResponse};
+use crate::models::transaction::TransactionResource;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+const MANIFEST_FILE: &str = "manifest.csv";
+const MANIFEST_HEADER: [&str; 7] = [
+    "attachment_id",
+    "transaction_id",
+    "file_name",
+    "date",
+    "description",
+    "amount",
+    "currency",
+];
+const MAX_DESCRIPTION_LENGTH: usize = 40;
+
+#[derive(Debug, Default)]
+pub struct ReceiptArchiveSummary {
+    pub downloaded: usize,
+    /// Attachments already listed in the manifest by a previous run.
+    pub already_archived: usize,
+    pub failed: Vec<(String, ClientError)>,
+}
+
+/// Downloads every attachment into a directory, named after its transaction, alongside a
+/// `manifest.csv` linking each file to its transaction ID.
+///
+/// Attachments already recorded in the manifest are skipped, so an interrupted export can
+/// simply be run again.
+#[derive(Debug, Clone)]
+pub struct ReceiptArchive {
+    directory: PathBuf,
+    page_size: u32,
+}
+
+impl ReceiptArchive {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            page_size: 100,
+        }
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    pub async fn export(&self, client: &Client) -> Result<ReceiptArchiveSummary, ClientError> {
+        fs::create_dir_all(&self.directory).await?;
+
+        let manifest_path = self.directory.join(MANIFEST_FILE);
+        let archived = read_manifest(&manifest_path).await?;
+        let mut manifest = open_manifest(&manifest_path).await?;
+
+        let mut summary = ReceiptArchiveSummary::default();
+        let mut page = client.list_attachments(Some(self.page_size)).await?;
+
+        loop {
+            for attachment in &page.data {
+                if archived.contains(&attachment.id) {
+                    summary.already_archived += 1;
+                    continue;
+                }
+
+                match self.archive(client, attachment).await {
+                    Ok(row) => {
+                        manifest.write_all(row.as_bytes()).await?;
+                        manifest.flush().await?;
+                        summary.downloaded += 1;
+                    }
+                    Err(e) => summary.failed.push((attachment.id.clone(), e)),
+                }
+            }
+
+            match page.links.next.as_deref() {
+                Some(next) => page = client.get_page::<AttachmentsResponse>(next).await?,
+                None => break,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    // Downloads one attachment and returns its manifest row.
+    async fn archive(
+        &self,
+        client: &Client,
+        attachment: &AttachmentResource,
+    ) -> Result<String, ClientError> {
+        let transaction_id = &attachment.relationships.transaction.data.id;
+        let transaction = client.get_transaction(transaction_id).await?.data;
+
+        let extension = attachment
+            .attributes
+            .file_extension
+            .as_deref()
+            .map(|ext| ext.trim_start_matches('.'))
+            .filter(|ext| !ext.is_empty())
+            .unwrap_or("bin");
+        let file_name =
+            unused_file_name(&self.directory, &file_stem(&transaction), extension).await?;
+
+        // Download to a temporary name so a half-written file is never mistaken for a receipt.
+        let final_path = self.directory.join(&file_name);
+        let partial_path = self.directory.join(format!("{}.part", file_name));
+        let mut file = File::create(&partial_path).await?;
+        let downloaded = client
+            .download_attachment_resource(attachment, &mut file)
+            .await;
+        drop(file);
+        if let Err(e) = downloaded {
+            let _ = fs::remove_file(&partial_path).await;
+            return Err(e);
+        }
+        fs::rename(&partial_path, &final_path).await?;
+
+        let attributes = &transaction.attributes;
+        Ok(csv_row([
+            attachment.id.as_str(),
+            transaction.id.as_str(),
+            file_name.as_str(),
+            transaction_date(&transaction),
+            attributes.description.as_str(),
+            attributes.amount.value.as_str(),
+            attributes.amount.currency_code.as_str(),
+        ]))
+    }
+}
+
+async fn read_manifest(path: &Path) -> Result<HashSet<String>, ClientError> {
+    let contents = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Attachment IDs are UUIDs, so the first field never needs unquoting.
+    Ok(contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split(',').next())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+async fn open_manifest(path: &Path) -> Result<File, ClientError> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    if file.metadata().await?.len() == 0 {
+        file.write_all(csv_row(MANIFEST_HEADER).as_bytes()).await?;
+    }
+    Ok(file)
+}
+
+fn transaction_date(transaction: &TransactionResource) -> &str {
+    let timestamp = posted_at(transaction);
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+fn file_stem(transaction: &TransactionResource) -> String {
+    let mut description = String::new();
+    for c in transaction.attributes.description.chars() {
+        if c.is_alphanumeric() {
+            description.push(c);
+        } else if !description.ends_with('-') {
+            description.push('-');
+        }
+    }
+    let description: String = description
+        .trim_matches('-')
+        .chars()
+        .take(MAX_DESCRIPTION_LENGTH)
+        .collect();
+
+    format!(
+        "{}_{}_{}",
+        transaction_date(transaction),
+        description.trim_end_matches('-'),
+        transaction.attributes.amount.value
+    )
+}
+
+async fn unused_file_name(
+    directory: &Path,
+    stem: &str,
+    extension: &str,
+) -> Result<String, ClientError> {
+    let mut file_name = format!("{}.{}", stem, extension);
+    let mut counter = 2;
+    while fs::try_exists(directory.join(&file_name)).await? {
+        file_name = format!("{}-{}.{}", stem, counter, extension);
+        counter += 1;
+    }
+    Ok(file_name)
+}
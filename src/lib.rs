@@ -1,19 +1,58 @@
+// reqwest has no TLS backend of its own, and the API is only served over HTTPS.
+#[cfg(all(
+    feature = "client",
+    not(any(feature = "default-tls", feature = "native-tls", feature = "rustls"))
+))]
+compile_error!(
+    "uprusty: the `client` feature needs a TLS backend; enable `default-tls`, `native-tls` or `rustls`"
+);
+
+#[cfg(feature = "client")]
+pub mod analysis;
+#[cfg(feature = "client")]
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "client")]
 mod client;
+#[cfg(feature = "client")]
 mod endpoints;
+#[cfg(feature = "client")]
 mod error;
+#[cfg(feature = "client")]
+pub mod export;
+#[cfg(feature = "client")]
+pub mod merchant;
 mod models;
+#[cfg(feature = "client")]
+pub mod rules;
+#[cfg(feature = "client")]
+pub mod transport;
 mod types;
 pub mod webhook;
 
-pub use client::Client;
+#[cfg(feature = "client")]
+pub use batch::{BatchExt, BatchItemResult, BatchOptions, BatchOutcome, BatchReport};
+#[cfg(feature = "client")]
+pub use client::{Client, ClientError};
+#[cfg(feature = "client")]
 pub use error::{Error, Result};
+#[cfg(feature = "client")]
+pub use merchant::{MerchantNormalizer, MerchantRule, RegexRule};
+#[cfg(feature = "client")]
+pub use rules::{Action, Condition, Rule, RuleEngine, RulePlan, RuleWebhookHandler};
+#[cfg(feature = "client")]
+pub use transport::{HttpRequest, HttpResponse, ReqwestTransport, Transport};
 
-pub use endpoints::accounts::AccountsExt;
-pub use endpoints::attachments::AttachmentsExt;
-pub use endpoints::categories::CategoriesExt;
-pub use endpoints::tags::TagsExt;
-pub use endpoints::transactions::{TransactionFilters, TransactionsExt};
-pub use endpoints::webhooks::WebhooksExt;
+#[cfg(feature = "client")]
+pub use endpoints::{
+    accounts::AccountsExt,
+    attachments::AttachmentsExt,
+    categories::CategoriesExt,
+    tags::{TagChanges, TagsExt, validate_tags},
+    transactions::{TransactionFilters, TransactionsExt},
+    webhooks::WebhooksExt,
+};
 
 pub use models::account::{
     AccountAttributes, AccountResource, AccountResponse, AccountType, AccountsResponse,
@@ -30,7 +69,10 @@ pub use models::category::{
     CategoryResource, CategoryResourceIdentifier, CategoryResponse,
 };
 
-pub use models::tags::{TagRelationships, TagResource, TagsResponse, TagsTransactionRequest};
+pub use models::tags::{
+    MAX_TAG_LENGTH, MAX_TAGS_PER_TRANSACTION, TagRelationships, TagResource, TagsResponse,
+    TagsTransactionRequest,
+};
 
 pub use models::transaction::{
     CardPurchaseMethod, Cashback, Customer, HoldInfo, Note, RoundUp, TransactionAttributes,
@@ -44,9 +86,17 @@ pub use models::webhooks::{
     WebhookResource, WebhookResponse, WebhooksResponse,
 };
 
-pub use types::money::MoneyObject;
+pub use types::jsonapi::{
+    Document, LinksRelationship, Paginated, PaginationLinks, Relationship, RelationshipLinks,
+    Resource, ResourceIdentifier, ResourceLinks,
+};
+pub use types::money::{MoneyObject, format_base_units};
+pub use types::responses::{ErrorObject, ErrorResponse, ErrorSource};
+pub use types::traits::RelatedLink;
 
+#[cfg(feature = "client")]
 pub mod prelude {
+    pub use crate::batch::{BatchExt, BatchOptions};
     pub use crate::client::Client;
     pub use crate::endpoints::accounts::AccountsExt;
     pub use crate::endpoints::attachments::AttachmentsExt;
@@ -55,5 +105,5 @@ pub mod prelude {
     pub use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
     pub use crate::endpoints::webhooks::WebhooksExt;
     pub use crate::types::money::MoneyObject;
-    pub use crate::webhook::{WebhookEventHandler, WebhookHandler};
+    pub use crate::webhook::{TransactionPoller, WebhookEventHandler, WebhookHandler};
 }
@@ -0,0 +1,205 @@
+use crate::models::transaction::TransactionResource;
+use regex::Regex;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+/// A rule that may map a transaction's text to a canonical merchant name.
+pub trait MerchantRule: Send + Sync {
+    /// Return the canonical merchant for `text`, or `None` to let later rules decide.
+    fn apply(&self, text: &str) -> Option<String>;
+}
+
+/// Maps any text matching a regular expression to a fixed merchant name.
+#[derive(Debug, Clone)]
+pub struct RegexRule {
+    pattern: Regex,
+    merchant: String,
+}
+
+impl RegexRule {
+    pub fn new(pattern: &str, merchant: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            merchant: merchant.to_string(),
+        })
+    }
+}
+
+impl MerchantRule for RegexRule {
+    fn apply(&self, text: &str) -> Option<String> {
+        self.pattern.is_match(text).then(|| self.merchant.clone())
+    }
+}
+
+static PROCESSOR_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(sq|square|paypal|pp|zlr|tst|sp|smp|ls|iz|cko|pos|eftpos|visa|dd|ezi|hl|cba)\s*\*\s*",
+    )
+    .unwrap()
+});
+static CARD_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(card\s*)?(x{2,}|\*{2,})\s*\d{4}\b").unwrap());
+// A marked store number (`#12`, `NO. 12`, `STORE 12`), or a bare number of three or more
+// digits with more text after it, e.g. the location in `WOOLWORTHS 1234 SYDNEY NSW`.
+static STORE_NUMBER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s(?:(?:#|no\.|store)\s*\d+\b|\d{3,}\s+\S).*$").unwrap());
+// Only capitalised codes, so names like `Boost Juice Act` are left alone.
+static LOCATION_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.*?(\S+))(\s+(NSW|VIC|QLD|SA|WA|TAS|NT|ACT|AU|AUS|AUSTRALIA))+\s*$").unwrap()
+});
+// Words that make a trailing state code part of the name, as in `TRANSPORT FOR NSW`.
+const CONNECTIVES: &[&str] = &["FOR", "OF", "THE", "AND", "&", "IN"];
+static DOMAIN_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\.(com|net|org)(\.au)?\b").unwrap());
+
+/// Derives a canonical merchant name from a transaction's `description` and `raw_text`.
+///
+/// User rules are tried first, against the raw text and then the description. When none
+/// match, the built-in heuristics clean up the description: payment processor prefixes
+/// (`SQ *`, `PAYPAL *`), card suffixes (`xx1234`), store numbers and anything after them,
+/// state and country codes following a capitalised suburb, and domain suffixes are
+/// removed.
+#[derive(Clone)]
+pub struct MerchantNormalizer {
+    rules: Vec<Arc<dyn MerchantRule>>,
+    heuristics: bool,
+}
+
+impl fmt::Debug for MerchantNormalizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MerchantNormalizer")
+            .field("rules", &self.rules.len())
+            .field("heuristics", &self.heuristics)
+            .finish()
+    }
+}
+
+impl Default for MerchantNormalizer {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            heuristics: true,
+        }
+    }
+}
+
+impl MerchantNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A normalizer that only applies user rules, falling back to the description as is.
+    pub fn without_heuristics() -> Self {
+        Self {
+            rules: Vec::new(),
+            heuristics: false,
+        }
+    }
+
+    /// Map text matching `pattern` to `merchant`.
+    pub fn regex_rule(self, pattern: &str, merchant: &str) -> Result<Self, regex::Error> {
+        Ok(self.rule(RegexRule::new(pattern, merchant)?))
+    }
+
+    pub fn rule(mut self, rule: impl MerchantRule + 'static) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    pub fn merchant(&self, transaction: &TransactionResource) -> String {
+        let attributes = &transaction.attributes;
+        let texts = attributes
+            .raw_text
+            .as_deref()
+            .into_iter()
+            .chain([attributes.description.as_str()]);
+
+        for text in texts {
+            if let Some(merchant) = self.rules.iter().find_map(|rule| rule.apply(text)) {
+                return merchant;
+            }
+        }
+
+        self.normalize(&attributes.description)
+    }
+
+    /// Apply the built-in heuristics (if enabled) to a piece of merchant text.
+    pub fn normalize(&self, text: &str) -> String {
+        if !self.heuristics {
+            return text.trim().to_string();
+        }
+
+        let text = PROCESSOR_PREFIX.replace(text, "");
+        let text = CARD_SUFFIX.replace_all(&text, "");
+        let text = STORE_NUMBER.replace(&text, "");
+        let text = strip_location(&text);
+        let text = DOMAIN_SUFFIX.replace_all(&text, "");
+
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '&'))
+            .filter(|word| !word.is_empty())
+            .collect();
+        if words.is_empty() {
+            return text.trim().to_string();
+        }
+
+        // Shouty raw text reads better in title case; mixed case is kept as the merchant wrote it.
+        let shouting = words
+            .iter()
+            .all(|word| !word.chars().any(char::is_lowercase));
+        words
+            .iter()
+            .map(|word| {
+                if shouting {
+                    title_case(word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl TransactionResource {
+    /// The canonical merchant of this transaction according to `normalizer`.
+    pub fn merchant(&self, normalizer: &MerchantNormalizer) -> String {
+        normalizer.merchant(self)
+    }
+}
+
+// Drop trailing state and country codes when they follow a suburb, which bank text writes in
+// capitals.
+fn strip_location(text: &str) -> Cow<'_, str> {
+    let Some(captures) = LOCATION_SUFFIX.captures(text) else {
+        return Cow::Borrowed(text);
+    };
+    let suburb = &captures[2];
+    let is_suburb = suburb.chars().any(char::is_alphabetic)
+        && !suburb.chars().any(char::is_lowercase)
+        && !CONNECTIVES.contains(&suburb);
+    if is_suburb {
+        Cow::Owned(captures[1].to_string())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+// Hyphenated names such as `7-ELEVEN` are capitalised on both sides of the hyphen.
+fn title_case(word: &str) -> String {
+    word.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("-")
+}
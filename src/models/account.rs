@@ -1,3 +1,4 @@
+use crate::types::jsonapi::{Document, LinksRelationship, Paginated, ResourceLinks};
 use crate::types::money::MoneyObject;
 use serde::{Deserialize, Serialize};
 
@@ -28,23 +29,7 @@ pub struct AccountAttributes {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccountRelationships {
-    pub transactions: TransactionRelationship,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TransactionRelationship {
-    pub links: Option<RelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct RelationshipLinks {
-    pub related: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AccountLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
+    pub transactions: LinksRelationship,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -54,22 +39,9 @@ pub struct AccountResource {
     pub id: String,
     pub attributes: AccountAttributes,
     pub relationships: AccountRelationships,
-    pub links: Option<AccountLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AccountsResponse {
-    pub data: Vec<AccountResource>,
-    pub links: PaginationLinks,
+    pub links: Option<ResourceLinks>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AccountResponse {
-    pub data: AccountResource,
-}
+pub type AccountsResponse = Paginated<AccountResource>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PaginationLinks {
-    pub prev: Option<String>,
-    pub next: Option<String>,
-}
+pub type AccountResponse = Document<AccountResource>;
@@ -1,3 +1,4 @@
+use crate::types::jsonapi::{Document, Paginated, Relationship, ResourceIdentifier, ResourceLinks};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -7,40 +8,18 @@ pub struct AttachmentAttributes {
     #[serde(rename = "fileURL")]
     pub file_url: Option<String>,
     #[serde(rename = "fileURLExpiresAt")]
-    pub file_url_expires_at: String,
+    pub file_url_expires_at: Option<String>,
     #[serde(rename = "fileExtension")]
     pub file_extension: Option<String>,
     #[serde(rename = "fileContentType")]
     pub file_content_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentTransactionData {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentTransactionRelationship {
-    pub data: AttachmentTransactionData,
-    pub links: Option<AttachmentRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentRelationshipLinks {
-    pub related: String,
-}
+pub type AttachmentTransactionData = ResourceIdentifier;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AttachmentRelationships {
-    pub transaction: AttachmentTransactionRelationship,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
+    pub transaction: Relationship<ResourceIdentifier>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -50,22 +29,9 @@ pub struct AttachmentResource {
     pub id: String,
     pub attributes: AttachmentAttributes,
     pub relationships: AttachmentRelationships,
-    pub links: Option<AttachmentLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentsResponse {
-    pub data: Vec<AttachmentResource>,
-    pub links: PaginationLinks,
+    pub links: Option<ResourceLinks>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentResponse {
-    pub data: AttachmentResource,
-}
+pub type AttachmentsResponse = Paginated<AttachmentResource>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PaginationLinks {
-    pub prev: Option<String>,
-    pub next: Option<String>,
-}
+pub type AttachmentResponse = Document<AttachmentResource>;
@@ -1,3 +1,4 @@
+use crate::types::jsonapi::{Document, Relationship, ResourceIdentifier, ResourceLinks};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -8,39 +9,11 @@ pub struct CategoryAttributes {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CategoryRelationships {
-    pub parent: CategoryParentRelationship,
-    pub children: CategoryChildrenRelationship,
+    pub parent: Relationship<Option<ResourceIdentifier>>,
+    pub children: Relationship<Vec<ResourceIdentifier>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryParentRelationship {
-    pub data: Option<CategoryResourceIdentifier>,
-    pub links: Option<CategoryRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryChildrenRelationship {
-    pub data: Vec<CategoryResourceIdentifier>,
-    pub links: Option<CategoryRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryRelationshipLinks {
-    pub related: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
-}
+pub type CategoryResourceIdentifier = ResourceIdentifier;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CategoryResource {
@@ -49,39 +22,24 @@ pub struct CategoryResource {
     pub id: String,
     pub attributes: CategoryAttributes,
     pub relationships: CategoryRelationships,
-    pub links: Option<CategoryLinks>,
+    pub links: Option<ResourceLinks>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoriesResponse {
-    pub data: Vec<CategoryResource>,
-}
+/// All categories; this list is not paginated.
+pub type CategoriesResponse = Document<Vec<CategoryResource>>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryResponse {
-    pub data: CategoryResource,
-}
+pub type CategoryResponse = Document<CategoryResource>;
 
 // For PATCH requests to categorize transactions
-#[derive(Debug, Clone, Serialize)]
-pub struct CategoryInputResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct CategorizeTransactionRequest {
-    pub data: Option<CategoryInputResourceIdentifier>,
+    pub data: Option<ResourceIdentifier>,
 }
 
 impl CategorizeTransactionRequest {
     pub fn new(category_id: &str) -> Self {
         Self {
-            data: Some(CategoryInputResourceIdentifier {
-                resource_type: "categories".to_string(),
-                id: category_id.to_string(),
-            }),
+            data: Some(ResourceIdentifier::new("categories", category_id)),
         }
     }
 
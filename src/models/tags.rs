@@ -1,18 +1,15 @@
+use crate::types::jsonapi::{LinksRelationship, Paginated, ResourceIdentifier};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagRelationships {
-    pub transactions: TagTransactionsRelationship,
-}
+/// Maximum number of tags Up allows on a single transaction.
+pub const MAX_TAGS_PER_TRANSACTION: usize = 6;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagTransactionsRelationship {
-    pub links: Option<TagRelationshipLinks>,
-}
+/// Maximum length of a tag label, in characters.
+pub const MAX_TAG_LENGTH: usize = 30;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagRelationshipLinks {
-    pub related: String,
+pub struct TagRelationships {
+    pub transactions: LinksRelationship,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -23,29 +20,12 @@ pub struct TagResource {
     pub relationships: TagRelationships,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagsResponse {
-    pub data: Vec<TagResource>,
-    pub links: PaginationLinks,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PaginationLinks {
-    pub prev: Option<String>,
-    pub next: Option<String>,
-}
+pub type TagsResponse = Paginated<TagResource>;
 
 // For POST/DELETE requests to add/remove tags from transactions
-#[derive(Debug, Clone, Serialize)]
-pub struct TagInputResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct TagsTransactionRequest {
-    pub data: Vec<TagInputResourceIdentifier>,
+    pub data: Vec<ResourceIdentifier>,
 }
 
 impl TagsTransactionRequest {
@@ -53,10 +33,7 @@ impl TagsTransactionRequest {
         Self {
             data: tag_ids
                 .into_iter()
-                .map(|id| TagInputResourceIdentifier {
-                    resource_type: "tags".to_string(),
-                    id: id.to_string(),
-                })
+                .map(|id| ResourceIdentifier::new("tags", id))
                 .collect(),
         }
     }
@@ -1,3 +1,4 @@
+use crate::types::jsonapi::{Document, Paginated, Relationship, ResourceIdentifier, ResourceLinks};
 use crate::types::money::MoneyObject;
 use serde::{Deserialize, Serialize};
 
@@ -8,7 +9,7 @@ pub enum TransactionStatus {
     Settled,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CardPurchaseMethod {
     BarCode,
@@ -85,88 +86,14 @@ pub struct Customer {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TransactionRelationships {
-    pub account: AccountRelationship,
+    pub account: Relationship<ResourceIdentifier>,
     #[serde(rename = "transferAccount")]
-    pub transfer_account: TransferAccountRelationship,
-    pub category: CategoryRelationship,
+    pub transfer_account: Relationship<Option<ResourceIdentifier>>,
+    pub category: Relationship<Option<ResourceIdentifier>>,
     #[serde(rename = "parentCategory")]
-    pub parent_category: ParentCategoryRelationship,
-    pub tags: TagsRelationship,
-    pub attachment: AttachmentRelationship,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AccountRelationship {
-    pub data: ResourceIdentifier,
-    pub links: Option<RelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TransferAccountRelationship {
-    pub data: Option<ResourceIdentifier>,
-    pub links: Option<RelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryRelationship {
-    pub data: Option<ResourceIdentifier>,
-    pub links: Option<CategoryRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ParentCategoryRelationship {
-    pub data: Option<ResourceIdentifier>,
-    pub links: Option<RelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagsRelationship {
-    pub data: Vec<TagResourceIdentifier>,
-    pub links: Option<TagsRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct AttachmentRelationship {
-    pub data: Option<ResourceIdentifier>,
-    pub links: Option<RelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct RelationshipLinks {
-    pub related: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CategoryRelationshipLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
-    pub related: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TagsRelationshipLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TransactionLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
+    pub parent_category: Relationship<Option<ResourceIdentifier>>,
+    pub tags: Relationship<Vec<ResourceIdentifier>>,
+    pub attachment: Relationship<Option<ResourceIdentifier>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -176,22 +103,9 @@ pub struct TransactionResource {
     pub id: String,
     pub attributes: TransactionAttributes,
     pub relationships: TransactionRelationships,
-    pub links: Option<TransactionLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TransactionsResponse {
-    pub data: Vec<TransactionResource>,
-    pub links: PaginationLinks,
+    pub links: Option<ResourceLinks>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TransactionResponse {
-    pub data: TransactionResource,
-}
+pub type TransactionsResponse = Paginated<TransactionResource>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PaginationLinks {
-    pub prev: Option<String>,
-    pub next: Option<String>,
-}
+pub type TransactionResponse = Document<TransactionResource>;
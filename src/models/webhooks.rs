@@ -1,3 +1,6 @@
+use crate::types::jsonapi::{
+    Document, LinksRelationship, Paginated, Relationship, ResourceIdentifier, ResourceLinks,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -11,23 +14,7 @@ pub struct WebhookAttributes {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WebhookRelationships {
-    pub logs: WebhookLogsRelationship,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookLogsRelationship {
-    pub links: Option<WebhookRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookRelationshipLinks {
-    pub related: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookLinks {
-    #[serde(rename = "self")]
-    pub self_link: String,
+    pub logs: LinksRelationship,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -37,26 +24,13 @@ pub struct WebhookResource {
     pub id: String,
     pub attributes: WebhookAttributes,
     pub relationships: WebhookRelationships,
-    pub links: Option<WebhookLinks>,
+    pub links: Option<ResourceLinks>,
 }
 
 // Webhook response types
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhooksResponse {
-    pub data: Vec<WebhookResource>,
-    pub links: PaginationLinks,
-}
+pub type WebhooksResponse = Paginated<WebhookResource>;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookResponse {
-    pub data: WebhookResource,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct PaginationLinks {
-    pub prev: Option<String>,
-    pub next: Option<String>,
-}
+pub type WebhookResponse = Document<WebhookResource>;
 
 // Webhook creation request
 #[derive(Debug, Clone, Serialize)]
@@ -107,32 +81,8 @@ pub struct WebhookEventAttributes {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WebhookEventRelationships {
-    pub webhook: WebhookEventWebhookRelationship,
-    pub transaction: Option<WebhookEventTransactionRelationship>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookEventWebhookRelationship {
-    pub data: ResourceIdentifier,
-    pub links: Option<WebhookEventRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookEventTransactionRelationship {
-    pub data: ResourceIdentifier,
-    pub links: Option<WebhookEventRelationshipLinks>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ResourceIdentifier {
-    #[serde(rename = "type")]
-    pub resource_type: String,
-    pub id: String,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookEventRelationshipLinks {
-    pub related: String,
+    pub webhook: Relationship<ResourceIdentifier>,
+    pub transaction: Option<Relationship<ResourceIdentifier>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -144,10 +94,7 @@ pub struct WebhookEventResource {
     pub relationships: WebhookEventRelationships,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookEventResponse {
-    pub data: WebhookEventResource,
-}
+pub type WebhookEventResponse = Document<WebhookEventResource>;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -181,12 +128,7 @@ pub struct WebhookDeliveryLogAttributes {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WebhookDeliveryLogRelationships {
     #[serde(rename = "webhookEvent")]
-    pub webhook_event: WebhookEventRelationship,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookEventRelationship {
-    pub data: ResourceIdentifier,
+    pub webhook_event: Relationship<ResourceIdentifier>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -198,8 +140,4 @@ pub struct WebhookDeliveryLogResource {
     pub relationships: WebhookDeliveryLogRelationships,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct WebhookDeliveryLogsResponse {
-    pub data: Vec<WebhookDeliveryLogResource>,
-    pub links: PaginationLinks,
-}
+pub type WebhookDeliveryLogsResponse = Paginated<WebhookDeliveryLogResource>;
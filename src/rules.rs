@@ -0,0 +1,363 @@
+use crate::batch::{BatchOptions, BatchOutcome, BatchReport, run_batch};
+use crate::client::{Client, ClientError};
+use crate::endpoints::categories::CategoriesExt;
+use crate::endpoints::tags::TagsExt;
+use crate::models::tags::MAX_TAGS_PER_TRANSACTION;
+use crate::models::transaction::{CardPurchaseMethod, TransactionResource};
+use crate::models::webhooks::WebhookEventResource;
+use crate::webhook::WebhookEventHandler;
+use crate::webhook::fetcher::TransactionFetcher;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// The description or raw text matches the expression.
+    DescriptionMatches(Regex),
+    /// The amount, in base units, is within the range. Purchases are negative.
+    AmountInRange(Bound<i64>, Bound<i64>),
+    CardPurchaseMethod(CardPurchaseMethod),
+    Account(String),
+    /// The transaction has a foreign amount, in the given currency if one is set.
+    ForeignCurrency(Option<String>),
+    /// The display name of the customer who made the transaction, e.g. on a 2Up account.
+    PerformingCustomer(String),
+}
+
+impl Condition {
+    pub fn matches(&self, transaction: &TransactionResource) -> bool {
+        let attributes = &transaction.attributes;
+        match self {
+            Condition::DescriptionMatches(pattern) => {
+                pattern.is_match(&attributes.description)
+                    || attributes
+                        .raw_text
+                        .as_deref()
+                        .is_some_and(|raw| pattern.is_match(raw))
+            }
+            Condition::AmountInRange(start, end) => {
+                (*start, *end).contains(&attributes.amount.value_in_base_units)
+            }
+            Condition::CardPurchaseMethod(method) => attributes
+                .card_purchase_method
+                .as_ref()
+                .is_some_and(|info| info.method == *method),
+            Condition::Account(account_id) => {
+                transaction.relationships.account.data.id == *account_id
+            }
+            Condition::ForeignCurrency(currency) => {
+                attributes
+                    .foreign_amount
+                    .as_ref()
+                    .is_some_and(|foreign| match currency {
+                        Some(code) => foreign.currency_code.eq_ignore_ascii_case(code),
+                        None => true,
+                    })
+            }
+            Condition::PerformingCustomer(name) => attributes
+                .performing_customer
+                .as_ref()
+                .is_some_and(|customer| customer.display_name == *name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Action {
+    SetCategory(String),
+    AddTags(Vec<String>),
+}
+
+/// A set of conditions that must all hold, and the actions to take when they do.
+/// A rule without conditions matches every transaction.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub conditions: Vec<Condition>,
+    pub actions: Vec<Action>,
+}
+
+impl Rule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            conditions: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn description_matches(self, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(self.condition(Condition::DescriptionMatches(Regex::new(pattern)?)))
+    }
+
+    pub fn amount_in_range(self, range: impl RangeBounds<i64>) -> Self {
+        self.condition(Condition::AmountInRange(
+            range.start_bound().cloned(),
+            range.end_bound().cloned(),
+        ))
+    }
+
+    pub fn card_purchase_method(self, method: CardPurchaseMethod) -> Self {
+        self.condition(Condition::CardPurchaseMethod(method))
+    }
+
+    pub fn account(self, account_id: &str) -> Self {
+        self.condition(Condition::Account(account_id.to_string()))
+    }
+
+    pub fn foreign_currency(self, currency_code: Option<&str>) -> Self {
+        self.condition(Condition::ForeignCurrency(
+            currency_code.map(str::to_string),
+        ))
+    }
+
+    pub fn performing_customer(self, display_name: &str) -> Self {
+        self.condition(Condition::PerformingCustomer(display_name.to_string()))
+    }
+
+    pub fn set_category(mut self, category_id: &str) -> Self {
+        self.actions
+            .push(Action::SetCategory(category_id.to_string()));
+        self
+    }
+
+    pub fn add_tags(mut self, tag_ids: &[&str]) -> Self {
+        self.actions.push(Action::AddTags(
+            tag_ids.iter().map(|tag| tag.to_string()).collect(),
+        ));
+        self
+    }
+
+    pub fn matches(&self, transaction: &TransactionResource) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(transaction))
+    }
+}
+
+/// The changes the rules would make to one transaction. Changes already in place
+/// (the same category, existing tags) are left out.
+#[derive(Debug, Clone)]
+pub struct RulePlan {
+    pub transaction_id: String,
+    /// Names of the rules that matched, in order.
+    pub rules: Vec<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl RulePlan {
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.tags.is_empty()
+    }
+}
+
+/// Applies an ordered list of rules to transactions.
+///
+/// Every matching rule contributes: the first matching rule that sets a category decides
+/// the category, and tags from all matching rules are combined. Tags that would take a
+/// transaction over Up's limit are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Work out the changes for one transaction without making them.
+    pub fn plan(&self, transaction: &TransactionResource) -> Option<RulePlan> {
+        let relationships = &transaction.relationships;
+        let current_category = relationships.category.data.as_ref().map(|c| c.id.as_str());
+        let current_tags: Vec<&str> = relationships
+            .tags
+            .data
+            .iter()
+            .map(|tag| tag.id.as_str())
+            .collect();
+
+        let mut plan = RulePlan {
+            transaction_id: transaction.id.clone(),
+            rules: Vec::new(),
+            category: None,
+            tags: Vec::new(),
+        };
+        let mut category_decided = false;
+
+        for rule in self.rules.iter().filter(|rule| rule.matches(transaction)) {
+            plan.rules.push(rule.name.clone());
+            for action in &rule.actions {
+                match action {
+                    Action::SetCategory(category_id) if !category_decided => {
+                        category_decided = true;
+                        if transaction.attributes.is_categorizable
+                            && current_category != Some(category_id.as_str())
+                        {
+                            plan.category = Some(category_id.clone());
+                        }
+                    }
+                    Action::SetCategory(_) => {}
+                    Action::AddTags(tag_ids) => {
+                        for tag in tag_ids {
+                            if !current_tags.contains(&tag.as_str())
+                                && !plan.tags.contains(tag)
+                                && current_tags.len() + plan.tags.len() < MAX_TAGS_PER_TRANSACTION
+                            {
+                                plan.tags.push(tag.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        (!plan.rules.is_empty()).then_some(plan)
+    }
+
+    /// Plans for every transaction matched by at least one rule.
+    pub fn plan_all(&self, transactions: &[TransactionResource]) -> Vec<RulePlan> {
+        transactions
+            .iter()
+            .filter_map(|transaction| self.plan(transaction))
+            .collect()
+    }
+
+    /// Apply the rules to one transaction, returning the plan that was carried out.
+    pub async fn apply(
+        &self,
+        client: &Client,
+        transaction: &TransactionResource,
+    ) -> Result<Option<RulePlan>, ClientError> {
+        let Some(plan) = self.plan(transaction) else {
+            return Ok(None);
+        };
+        execute(client, &plan).await?;
+        Ok(Some(plan))
+    }
+
+    /// Apply the rules to a batch of transactions, such as a full history. Only
+    /// transactions with changes to make appear in the report.
+    pub async fn apply_all(
+        &self,
+        client: &Client,
+        transactions: &[TransactionResource],
+        options: BatchOptions,
+    ) -> BatchReport {
+        let plans: HashMap<String, RulePlan> = self
+            .plan_all(transactions)
+            .into_iter()
+            .filter(|plan| !plan.is_empty())
+            .map(|plan| (plan.transaction_id.clone(), plan))
+            .collect();
+        let ids: Vec<&str> = transactions
+            .iter()
+            .map(|transaction| transaction.id.as_str())
+            .filter(|id| plans.contains_key(*id))
+            .collect();
+        let plans = Arc::new(plans);
+
+        run_batch(client, &ids, options, move |client, id| {
+            let plans = Arc::clone(&plans);
+            async move {
+                execute(&client, &plans[&id]).await?;
+                Ok(BatchOutcome::Succeeded)
+            }
+        })
+        .await
+    }
+}
+
+async fn execute(client: &Client, plan: &RulePlan) -> Result<(), ClientError> {
+    if let Some(category_id) = &plan.category {
+        client
+            .categorize_transaction(&plan.transaction_id, Some(category_id))
+            .await?;
+    }
+    if !plan.tags.is_empty() {
+        let tags = plan.tags.iter().map(String::as_str).collect();
+        client
+            .add_tags_to_transaction(&plan.transaction_id, tags)
+            .await?;
+    }
+    Ok(())
+}
+
+type ResultCallback = dyn Fn(&str, Result<Option<RulePlan>, ClientError>) + Send + Sync;
+
+/// Runs a [`RuleEngine`] on every new transaction reported by a webhook. Transactions are
+/// fetched and updated in the background on the given runtime.
+#[derive(Clone)]
+pub struct RuleWebhookHandler {
+    engine: Arc<RuleEngine>,
+    fetcher: TransactionFetcher,
+    on_result: Option<Arc<ResultCallback>>,
+}
+
+impl fmt::Debug for RuleWebhookHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuleWebhookHandler")
+            .field("engine", &self.engine)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RuleWebhookHandler {
+    /// `runtime` is usually `Handle::current()`, taken from within the application's
+    /// Tokio runtime.
+    pub fn new(engine: RuleEngine, client: Client, runtime: Handle) -> Self {
+        Self {
+            engine: Arc::new(engine),
+            fetcher: TransactionFetcher::new(client, runtime),
+            on_result: None,
+        }
+    }
+
+    /// Called with the outcome for each transaction once its rules have run.
+    pub fn on_result<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str, Result<Option<RulePlan>, ClientError>) + Send + Sync + 'static,
+    {
+        self.on_result = Some(Arc::new(callback));
+        self
+    }
+}
+
+impl WebhookEventHandler for RuleWebhookHandler {
+    fn on_transaction_created(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        let engine = Arc::clone(&self.engine);
+        let on_result = self.on_result.clone();
+
+        self.fetcher.spawn(
+            transaction_id,
+            |client, transaction_id, result| async move {
+                let result = match result {
+                    Ok(transaction) => engine.apply(&client, &transaction).await,
+                    Err(e) => Err(e),
+                };
+                if let Some(callback) = on_result {
+                    callback(&transaction_id, result);
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use crate::client::ClientError;
+use async_trait::async_trait;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
+
+pub mod cassette;
+
+pub use cassette::{Cassette, RecordingTransport, ReplayTransport};
+pub use reqwest::{Method, StatusCode, header};
+
+/// An HTTP request, ready to be sent by a [`Transport`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: url::Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+/// The body of an [`HttpResponse`], read one chunk at a time.
+#[async_trait]
+pub trait ResponseBody: Send {
+    /// The next chunk of the body, or `None` once it has all been read.
+    async fn chunk(&mut self) -> Result<Option<Vec<u8>>, ClientError>;
+}
+
+struct BufferedBody(Option<Vec<u8>>);
+
+#[async_trait]
+impl ResponseBody for BufferedBody {
+    async fn chunk(&mut self) -> Result<Option<Vec<u8>>, ClientError> {
+        Ok(self.0.take().filter(|body| !body.is_empty()))
+    }
+}
+
+pub struct HttpResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Box<dyn ResponseBody>,
+}
+
+impl fmt::Debug for HttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpResponse {
+    /// A response whose body is already in memory.
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Vec<u8>) -> Self {
+        Self::streaming(status, headers, BufferedBody(Some(body)))
+    }
+
+    pub fn streaming(
+        status: StatusCode,
+        headers: HeaderMap,
+        body: impl ResponseBody + 'static,
+    ) -> Self {
+        Self {
+            status,
+            headers,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, ClientError> {
+        self.body.chunk().await
+    }
+
+    /// Read the rest of the body.
+    pub async fn bytes(mut self) -> Result<Vec<u8>, ClientError> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T, ClientError> {
+        let bytes = self.bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Turn a 4xx or 5xx response into `ClientError::HttpStatus`, keeping its body.
+    pub async fn error_for_status(self) -> Result<Self, ClientError> {
+        let status = self.status;
+        if !status.is_client_error() && !status.is_server_error() {
+            return Ok(self);
+        }
+        let body = self.bytes().await?;
+        Err(ClientError::HttpStatus {
+            status,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+}
+
+/// Sends HTTP requests for a [`Client`](crate::Client).
+///
+/// [`ReqwestTransport`] is used by default. Other implementations can mock the API,
+/// record traffic, or wrap another HTTP stack or middleware.
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        (**self).send(request).await
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a configured `reqwest::Client`, e.g. with a proxy or timeouts.
+    pub fn with_client(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+}
+
+struct ReqwestBody(reqwest::Response);
+
+#[async_trait]
+impl ResponseBody for ReqwestBody {
+    async fn chunk(&mut self) -> Result<Option<Vec<u8>>, ClientError> {
+        Ok(self.0.chunk().await?.map(|chunk| chunk.to_vec()))
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let mut builder = self
+            .http
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send().await?;
+        Ok(HttpResponse::streaming(
+            response.status(),
+            response.headers().clone(),
+            ReqwestBody(response),
+        ))
+    }
+}
+
+/// Builds an [`HttpRequest`] and sends it through the client's transport.
+#[derive(Debug)]
+pub struct RequestBuilder {
+    transport: Arc<dyn Transport>,
+    request: HttpRequest,
+    error: Option<ClientError>,
+}
+
+impl RequestBuilder {
+    pub(crate) fn new(transport: Arc<dyn Transport>, method: Method, url: url::Url) -> Self {
+        Self {
+            transport,
+            request: HttpRequest {
+                method,
+                url,
+                headers: HeaderMap::new(),
+                body: None,
+            },
+            error: None,
+        }
+    }
+
+    pub fn header<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        K::Error: std::error::Error + Send + Sync + 'static,
+        V: TryInto<HeaderValue>,
+        V::Error: std::error::Error + Send + Sync + 'static,
+    {
+        match (name.try_into(), value.try_into()) {
+            (Ok(name), Ok(value)) => {
+                self.request.headers.insert(name, value);
+            }
+            (Err(e), _) => self.error = Some(ClientError::Transport(Box::new(e))),
+            (_, Err(e)) => self.error = Some(ClientError::Transport(Box::new(e))),
+        }
+        self
+    }
+
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.request.headers.extend(headers);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.request.body = Some(body.into());
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        match serde_json::to_vec(body) {
+            Ok(body) => {
+                self.request.body = Some(body);
+                self.request
+                    .headers
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+            }
+            Err(e) => self.error = Some(e.into()),
+        }
+        self
+    }
+
+    pub async fn send(self) -> Result<HttpResponse, ClientError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.transport.send(self.request).await
+    }
+}
@@ -0,0 +1,392 @@
+//! Record real API traffic to a fixture file once, then replay it without a token.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), uprusty::ClientError> {
+//! use std::sync::Arc;
+//! use uprusty::prelude::*;
+//! use uprusty::transport::{RecordingTransport, ReplayTransport};
+//!
+//! // Once, with a real token:
+//! let recorder = Arc::new(RecordingTransport::new());
+//! let client = Client::with_transport("up:yeah:real_token", Arc::clone(&recorder))?;
+//! client.list_accounts(None, None, None).await?;
+//! recorder.save("tests/fixtures/cassettes/accounts.json")?;
+//!
+//! // In CI:
+//! let replay = ReplayTransport::load("tests/fixtures/cassettes/accounts.json")?;
+//! let client = Client::with_transport("up:yeah:replay", replay)?;
+//! let accounts = client.list_accounts(None, None, None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::ClientError;
+use crate::transport::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use crate::transport::{HttpRequest, HttpResponse, ReqwestTransport, StatusCode, Transport};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// A list of request/response pairs, as stored in a fixture file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let contents = fs::read(path)?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ClientError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = serde_json::to_vec_pretty(self)?;
+        contents.push(b'\n');
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A recorded request. Headers are not kept, so the bearer token never reaches the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "RecordedBody::is_empty")]
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "RecordedBody::is_empty")]
+    pub body: RecordedBody,
+}
+
+/// A request or response body. JSON is stored as-is so fixtures stay readable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedBody {
+    #[default]
+    Empty,
+    Json(Value),
+    Text(String),
+    /// Binary content, e.g. an attachment file, hex encoded.
+    Hex(String),
+}
+
+impl RecordedBody {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        if bytes.is_empty() {
+            return RecordedBody::Empty;
+        }
+        if let Ok(json) = serde_json::from_slice(bytes) {
+            return RecordedBody::Json(json);
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Hex(hex::encode(bytes)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ClientError> {
+        Ok(match self {
+            RecordedBody::Empty => Vec::new(),
+            RecordedBody::Json(json) => serde_json::to_vec(json)?,
+            RecordedBody::Text(text) => text.clone().into_bytes(),
+            RecordedBody::Hex(encoded) => {
+                hex::decode(encoded).map_err(|e| ClientError::Transport(Box::new(e)))?
+            }
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, RecordedBody::Empty)
+    }
+
+    fn replace(&mut self, from: &str, to: &str) -> Result<(), ClientError> {
+        match self {
+            RecordedBody::Json(json) => {
+                let replaced = serde_json::to_string(json)?.replace(from, to);
+                *json = serde_json::from_str(&replaced)?;
+            }
+            RecordedBody::Text(text) => *text = text.replace(from, to),
+            RecordedBody::Empty | RecordedBody::Hex(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Sends requests through another transport and keeps every request/response pair, to be
+/// written out with [`save`](Self::save).
+///
+/// Saved cassettes never contain request headers, so the bearer token is left out.
+/// Account IDs, found in `accounts` resources and in `accounts/{id}` URLs, are replaced
+/// with placeholders such as `redacted-account-1` throughout the file. Pre-signed
+/// attachment `fileURL`s, and requests to them, lose their query string and with it the
+/// signature.
+#[derive(Debug, Default)]
+pub struct RecordingTransport<T = ReqwestTransport> {
+    inner: T,
+    redactions: Vec<(String, String)>,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl RecordingTransport {
+    /// Record requests sent with reqwest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn with_transport(inner: T) -> Self {
+        Self {
+            inner,
+            redactions: Vec::new(),
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Also replace `secret` with `placeholder` wherever it appears, e.g. a customer name.
+    pub fn redact(mut self, secret: &str, placeholder: &str) -> Self {
+        self.redactions
+            .push((secret.to_string(), placeholder.to_string()));
+        self
+    }
+
+    /// Everything recorded so far, redacted.
+    pub fn cassette(&self) -> Result<Cassette, ClientError> {
+        let mut interactions = self.lock().clone();
+
+        let mut redactions = self.redactions.clone();
+        for (n, account_id) in account_ids(&interactions).into_iter().enumerate() {
+            redactions.push((account_id, format!("redacted-account-{}", n + 1)));
+        }
+
+        for interaction in &mut interactions {
+            interaction.request.url = without_signature(&interaction.request.url);
+            if let RecordedBody::Json(json) = &mut interaction.response.body {
+                redact_file_urls(json);
+            }
+            for (secret, placeholder) in &redactions {
+                interaction.request.url = interaction.request.url.replace(secret, placeholder);
+                interaction.request.body.replace(secret, placeholder)?;
+                interaction.response.body.replace(secret, placeholder)?;
+            }
+        }
+        Ok(Cassette { interactions })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ClientError> {
+        self.cassette()?.save(path)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Interaction>> {
+        self.interactions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let recorded_request = RecordedRequest {
+            method: request.method.to_string(),
+            url: request.url.to_string(),
+            body: RecordedBody::from_bytes(request.body.as_deref().unwrap_or_default()),
+        };
+
+        let response = self.inner.send(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+
+        self.lock().push(Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                content_type: headers
+                    .get(CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string),
+                body: RecordedBody::from_bytes(&body),
+            },
+        });
+        Ok(HttpResponse::new(status, headers, body))
+    }
+}
+
+/// Answers requests from a [`Cassette`] instead of the network.
+///
+/// Each request is matched on method, URL and body against the first recorded interaction
+/// not yet used, so repeated requests replay their responses in the order recorded.
+/// File downloads match regardless of the URL signature, which the recording dropped.
+/// Requests with no match fail with `ClientError::Transport`.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    interactions: Vec<Interaction>,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            interactions: cassette.interactions,
+            used: Mutex::new(used),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Whether every recorded interaction has been replayed.
+    pub fn is_exhausted(&self) -> bool {
+        self.lock().iter().all(|used| *used)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<bool>> {
+        self.used.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let method = request.method.to_string();
+        let url = without_signature(request.url.as_str());
+        let body = RecordedBody::from_bytes(request.body.as_deref().unwrap_or_default());
+
+        let interaction = {
+            let mut used = self.lock();
+            let index = self
+                .interactions
+                .iter()
+                .enumerate()
+                .position(|(i, interaction)| {
+                    !used[i]
+                        && interaction.request.method == method
+                        && interaction.request.url == url
+                        && interaction.request.body == body
+                })
+                .ok_or_else(|| {
+                    ClientError::Transport(
+                        format!("No recorded response for {} {}", method, url).into(),
+                    )
+                })?;
+            used[index] = true;
+            &self.interactions[index]
+        };
+
+        let response = &interaction.response;
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|e| ClientError::Transport(Box::new(e)))?;
+        let mut headers = HeaderMap::new();
+        if let Some(content_type) = &response.content_type {
+            let value = HeaderValue::from_str(content_type)
+                .map_err(|e| ClientError::Transport(Box::new(e)))?;
+            headers.insert(CONTENT_TYPE, value);
+        }
+        Ok(HttpResponse::new(
+            status,
+            headers,
+            response.body.to_bytes()?,
+        ))
+    }
+}
+
+const API_HOST: &str = "api.up.com.au";
+
+// Drop the query string of URLs outside the API, which holds the signature of a
+// pre-signed file URL.
+fn without_signature(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if parsed.host_str() != Some(API_HOST) => {
+            parsed.set_query(None);
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+fn redact_file_urls(json: &mut Value) {
+    match json {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match value {
+                    Value::String(url) if key == "fileURL" => *url = without_signature(url),
+                    value => redact_file_urls(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_file_urls),
+        _ => {}
+    }
+}
+
+// Account IDs in the order they first appear, from `accounts` resources and identifiers
+// in response bodies and from `accounts/{id}` request paths.
+fn account_ids(interactions: &[Interaction]) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+    let mut add = |id: &str| {
+        if !id.is_empty() && seen.insert(id.to_string()) {
+            ids.push(id.to_string());
+        }
+    };
+
+    for interaction in interactions {
+        if let Ok(url) = url::Url::parse(&interaction.request.url)
+            && let Some(segments) = url.path_segments()
+        {
+            let segments: Vec<&str> = segments.collect();
+            for pair in segments.windows(2) {
+                if pair[0] == "accounts" {
+                    add(pair[1]);
+                }
+            }
+        }
+        if let RecordedBody::Json(json) = &interaction.response.body {
+            collect_account_ids(json, &mut add);
+        }
+    }
+    ids
+}
+
+fn collect_account_ids(json: &Value, add: &mut impl FnMut(&str)) {
+    match json {
+        Value::Object(object) => {
+            if object.get("type").and_then(Value::as_str) == Some("accounts")
+                && let Some(id) = object.get("id").and_then(Value::as_str)
+            {
+                add(id);
+            }
+            for value in object.values() {
+                collect_account_ids(value, add);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_account_ids(value, add);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,3 +1,4 @@
+pub mod jsonapi;
 pub mod money;
 pub mod responses;
-mod traits;
+pub mod traits;
@@ -0,0 +1,113 @@
+//! JSON:API building blocks shared by every Up resource.
+
+use crate::types::traits::RelatedLink;
+use serde::{Deserialize, Serialize};
+
+/// The type and ID of a resource, as used in relationships and request bodies.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ResourceIdentifier {
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    pub id: String,
+}
+
+impl ResourceIdentifier {
+    pub fn new(resource_type: &str, id: &str) -> Self {
+        Self {
+            resource_type: resource_type.to_string(),
+            id: id.to_string(),
+        }
+    }
+}
+
+/// The canonical link of a resource.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResourceLinks {
+    #[serde(rename = "self")]
+    pub self_link: String,
+}
+
+/// Links of a relationship: `self` points at the relationship itself, `related` at the
+/// related resource(s). Either may be missing.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RelationshipLinks {
+    #[serde(rename = "self")]
+    pub self_link: Option<String>,
+    pub related: Option<String>,
+}
+
+impl RelatedLink for RelationshipLinks {
+    fn related(&self) -> Option<&str> {
+        self.related.as_deref()
+    }
+}
+
+/// A relationship with linkage data, e.g. `Option<ResourceIdentifier>` for to-one
+/// relationships and `Vec<ResourceIdentifier>` for to-many.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Relationship<D> {
+    pub data: D,
+    pub links: Option<RelationshipLinks>,
+}
+
+/// A relationship that only links to the related resources, without linkage data.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LinksRelationship {
+    pub links: Option<RelationshipLinks>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PaginationLinks {
+    pub prev: Option<String>,
+    pub next: Option<String>,
+}
+
+/// A page of resources, with links to the neighbouring pages.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Paginated<T> {
+    pub data: Vec<T>,
+    pub links: PaginationLinks,
+}
+
+/// A document holding a single resource, or an unpaginated list of them.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Document<T> {
+    pub data: T,
+}
+
+/// An Up resource, such as an account or transaction.
+pub trait Resource {
+    /// The JSON:API type, e.g. `"accounts"`.
+    const TYPE: &'static str;
+
+    fn id(&self) -> &str;
+
+    fn identifier(&self) -> ResourceIdentifier {
+        ResourceIdentifier::new(Self::TYPE, self.id())
+    }
+}
+
+macro_rules! impl_resource {
+    ($($resource:ty => $type:literal,)*) => {
+        $(
+            impl Resource for $resource {
+                const TYPE: &'static str = $type;
+
+                fn id(&self) -> &str {
+                    &self.id
+                }
+            }
+        )*
+    };
+}
+
+impl_resource! {
+    crate::models::account::AccountResource => "accounts",
+    crate::models::attachment::AttachmentResource => "attachments",
+    crate::models::category::CategoryResource => "categories",
+    crate::models::tags::TagResource => "tags",
+    crate::models::transaction::TransactionResource => "transactions",
+    crate::models::webhooks::WebhookResource => "webhooks",
+    crate::models::webhooks::WebhookEventResource => "webhook-events",
+    crate::models::webhooks::WebhookDeliveryLogResource => "webhook-delivery-logs",
+}
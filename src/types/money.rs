@@ -7,3 +7,39 @@ pub struct MoneyObject {
     pub value: String,
     pub value_in_base_units: i64,
 }
+
+impl MoneyObject {
+    /// Number of decimal places used by `value`, e.g. 2 for AUD and 0 for JPY.
+    pub fn decimal_places(&self) -> u32 {
+        self.value
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len() as u32)
+    }
+
+    /// An amount in the same currency, given in base units (e.g. cents).
+    pub fn with_base_units(&self, value_in_base_units: i64) -> MoneyObject {
+        MoneyObject {
+            currency_code: self.currency_code.clone(),
+            value: format_base_units(value_in_base_units, self.decimal_places()),
+            value_in_base_units,
+        }
+    }
+}
+
+/// Format an amount in base units as a decimal string, e.g. `-1250` with 2 places is `-12.50`.
+pub fn format_base_units(value_in_base_units: i64, decimal_places: u32) -> String {
+    let sign = if value_in_base_units < 0 { "-" } else { "" };
+    let units = value_in_base_units.unsigned_abs();
+    if decimal_places == 0 {
+        return format!("{}{}", sign, units);
+    }
+
+    let scale = 10u64.pow(decimal_places);
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        units / scale,
+        units % scale,
+        width = decimal_places as usize
+    )
+}
@@ -1 +1,13 @@
+/// A JSON:API relationship link that may point at the related resource(s).
+///
+/// Implemented by every relationship link type so the related resource can be fetched
+/// with [`Client::get_related`](crate::Client::get_related).
+pub trait RelatedLink {
+    fn related(&self) -> Option<&str>;
+}
 
+impl<T: RelatedLink> RelatedLink for Option<T> {
+    fn related(&self) -> Option<&str> {
+        self.as_ref().and_then(RelatedLink::related)
+    }
+}
@@ -2,6 +2,11 @@ use crate::models::webhooks::{WebhookEventResource, WebhookEventType};
 use std::error::Error;
 use std::fmt;
 
+#[cfg(feature = "client")]
+pub(crate) mod fetcher;
+#[cfg(feature = "client")]
+pub mod poller;
+
 pub mod verification {
     use super::*;
 
@@ -114,6 +119,33 @@ pub mod events {
         &event.relationships.webhook.data.id
     }
 
+    /// Call the `handler` method matching the event's type.
+    pub fn dispatch_event<H>(event: &WebhookEventResource, handler: &H)
+    where
+        H: WebhookEventHandler + ?Sized,
+    {
+        match event.attributes.event_type {
+            WebhookEventType::TransactionCreated => {
+                if let Some(transaction_id) = extract_transaction_id(event) {
+                    handler.on_transaction_created(transaction_id, event);
+                }
+            }
+            WebhookEventType::TransactionSettled => {
+                if let Some(transaction_id) = extract_transaction_id(event) {
+                    handler.on_transaction_settled(transaction_id, event);
+                }
+            }
+            WebhookEventType::TransactionDeleted => {
+                if let Some(transaction_id) = extract_transaction_id(event) {
+                    handler.on_transaction_deleted(transaction_id, event);
+                }
+            }
+            WebhookEventType::Ping => {
+                handler.on_ping(event);
+            }
+        }
+    }
+
     #[derive(Debug)]
     pub enum WebhookProcessingError {
         Verification(verification::VerificationError),
@@ -177,28 +209,7 @@ impl WebhookHandler {
         F: WebhookEventHandler,
     {
         let event = self.process_request(signature_header, raw_body)?;
-
-        match event.attributes.event_type {
-            WebhookEventType::TransactionCreated => {
-                if let Some(transaction_id) = events::extract_transaction_id(&event) {
-                    handler.on_transaction_created(transaction_id, &event);
-                }
-            }
-            WebhookEventType::TransactionSettled => {
-                if let Some(transaction_id) = events::extract_transaction_id(&event) {
-                    handler.on_transaction_settled(transaction_id, &event);
-                }
-            }
-            WebhookEventType::TransactionDeleted => {
-                if let Some(transaction_id) = events::extract_transaction_id(&event) {
-                    handler.on_transaction_deleted(transaction_id, &event);
-                }
-            }
-            WebhookEventType::Ping => {
-                handler.on_ping(&event);
-            }
-        }
-
+        events::dispatch_event(&event, &handler);
         Ok(())
     }
 }
@@ -221,5 +232,7 @@ pub trait WebhookEventHandler {
     }
 }
 
-pub use events::{WebhookProcessingError, parse_and_verify_event};
+pub use events::{WebhookProcessingError, dispatch_event, parse_and_verify_event};
+#[cfg(feature = "client")]
+pub use poller::TransactionPoller;
 pub use verification::{VerificationError, verify_signature};
@@ -0,0 +1,37 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::transactions::TransactionsExt;
+use crate::models::transaction::TransactionResource;
+use std::future::Future;
+use tokio::runtime::Handle;
+
+/// Fetches the transactions named by webhook events. Webhook handlers are synchronous, so
+/// each fetch, and whatever follows it, runs on a task spawned onto `runtime`.
+#[derive(Debug, Clone)]
+pub(crate) struct TransactionFetcher {
+    client: Client,
+    runtime: Handle,
+}
+
+impl TransactionFetcher {
+    pub(crate) fn new(client: Client, runtime: Handle) -> Self {
+        Self { client, runtime }
+    }
+
+    /// Fetch a transaction and pass the client, its ID and the result to `then`.
+    pub(crate) fn spawn<F, Fut>(&self, transaction_id: &str, then: F)
+    where
+        F: FnOnce(Client, String, Result<TransactionResource, ClientError>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let client = self.client.clone();
+        let transaction_id = transaction_id.to_string();
+
+        self.runtime.spawn(async move {
+            let result = client
+                .get_transaction(&transaction_id)
+                .await
+                .map(|response| response.data);
+            then(client, transaction_id, result).await;
+        });
+    }
+}
@@ -0,0 +1,218 @@
+use crate::client::{Client, ClientError};
+use crate::endpoints::transactions::{TransactionFilters, TransactionsExt};
+use crate::models::transaction::{TransactionResource, TransactionStatus};
+use crate::models::webhooks::{
+    WebhookEventAttributes, WebhookEventRelationships, WebhookEventResource, WebhookEventType,
+};
+use crate::types::jsonapi::{Relationship, ResourceIdentifier};
+use crate::webhook::{WebhookEventHandler, events};
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Webhook ID given to events produced by a poller.
+pub const POLLER_WEBHOOK_ID: &str = "poller";
+
+// The API applies `filter[since]` with its own clock, so transactions this close to the
+// start of the window may drop out of a response without having been deleted.
+const DELETION_MARGIN: TimeDelta = TimeDelta::minutes(5);
+
+#[derive(Debug, Clone)]
+struct Seen {
+    settled: bool,
+    created_at: DateTime<FixedOffset>,
+}
+
+/// Polls recent transactions and turns changes into webhook-style events, for deployments
+/// that cannot receive webhooks.
+///
+/// Each poll lists transactions created within the lookback window and compares them with
+/// the previous poll: new transactions produce `TRANSACTION_CREATED`, held transactions
+/// that have settled produce `TRANSACTION_SETTLED`, and transactions that vanished from the
+/// window produce `TRANSACTION_DELETED`. Transactions within a few minutes of the start of
+/// the window are never reported deleted, as clock differences with the API can drop them
+/// from a response. The window should be longer than holds usually take to settle, or
+/// their settlement is missed.
+///
+/// The first poll only records what already exists, unless `emit_existing` is set.
+#[derive(Debug, Clone)]
+pub struct TransactionPoller {
+    client: Client,
+    interval: Duration,
+    lookback: TimeDelta,
+    account_id: Option<String>,
+    emit_existing: bool,
+    seen: HashMap<String, Seen>,
+    primed: bool,
+}
+
+impl TransactionPoller {
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            interval: Duration::from_secs(30),
+            lookback: TimeDelta::days(7),
+            account_id: None,
+            emit_existing: false,
+            seen: HashMap::new(),
+            primed: false,
+        }
+    }
+
+    /// Time between polls in `run`.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn lookback(mut self, lookback: TimeDelta) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    /// Only poll one account's transactions.
+    pub fn account(mut self, account_id: &str) -> Self {
+        self.account_id = Some(account_id.to_string());
+        self
+    }
+
+    /// Emit `TRANSACTION_CREATED` for transactions found by the first poll.
+    pub fn emit_existing(mut self, emit_existing: bool) -> Self {
+        self.emit_existing = emit_existing;
+        self
+    }
+
+    /// Poll once, pass any events to `handler` and return them.
+    pub async fn poll_once<H>(
+        &mut self,
+        handler: &H,
+    ) -> Result<Vec<WebhookEventResource>, ClientError>
+    where
+        H: WebhookEventHandler + ?Sized,
+    {
+        let now = Local::now().fixed_offset();
+        let since = now - self.lookback;
+        let filters = TransactionFilters {
+            since: Some(since.to_rfc3339()),
+            ..Default::default()
+        };
+        let mut transactions = match &self.account_id {
+            Some(account_id) => {
+                self.client
+                    .list_all_account_transactions(account_id, Some(filters))
+                    .await?
+            }
+            None => self.client.list_all_transactions(Some(filters)).await?,
+        };
+        transactions.reverse();
+
+        let events = self.diff(&transactions, since, now);
+        for event in &events {
+            events::dispatch_event(event, handler);
+        }
+        Ok(events)
+    }
+
+    /// Poll every `interval` until a request fails. The poller keeps what it has seen, so
+    /// calling `run` again after an error carries on without repeating events.
+    pub async fn run<H>(&mut self, handler: &H) -> Result<(), ClientError>
+    where
+        H: WebhookEventHandler + ?Sized,
+    {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.poll_once(handler).await?;
+        }
+    }
+
+    fn diff(
+        &mut self,
+        transactions: &[TransactionResource],
+        since: DateTime<FixedOffset>,
+        now: DateTime<FixedOffset>,
+    ) -> Vec<WebhookEventResource> {
+        let emit = self.primed || self.emit_existing;
+        self.primed = true;
+
+        let mut events = Vec::new();
+        let mut current: HashMap<String, Seen> = HashMap::new();
+        for transaction in transactions {
+            let Ok(created_at) = DateTime::parse_from_rfc3339(&transaction.attributes.created_at)
+            else {
+                continue;
+            };
+            let settled = matches!(transaction.attributes.status, TransactionStatus::Settled);
+            let event_type = match self.seen.get(&transaction.id) {
+                None => Some(WebhookEventType::TransactionCreated),
+                Some(previous) if settled && !previous.settled => {
+                    Some(WebhookEventType::TransactionSettled)
+                }
+                Some(_) => None,
+            };
+            if let Some(event_type) = event_type.filter(|_| emit) {
+                events.push(poller_event(event_type, &transaction.id, now));
+            }
+            current.insert(
+                transaction.id.clone(),
+                Seen {
+                    settled,
+                    created_at,
+                },
+            );
+        }
+
+        // Anything well inside the window that the API no longer returns was deleted. Those
+        // near its start are kept, so they aren't created again if the next poll has them.
+        for (transaction_id, seen) in &self.seen {
+            if current.contains_key(transaction_id) || seen.created_at < since {
+                continue;
+            }
+            if seen.created_at >= since + DELETION_MARGIN {
+                events.push(poller_event(
+                    WebhookEventType::TransactionDeleted,
+                    transaction_id,
+                    now,
+                ));
+            } else {
+                current.insert(transaction_id.clone(), seen.clone());
+            }
+        }
+
+        self.seen = current;
+        events
+    }
+}
+
+fn poller_event(
+    event_type: WebhookEventType,
+    transaction_id: &str,
+    now: DateTime<FixedOffset>,
+) -> WebhookEventResource {
+    let kind = match event_type {
+        WebhookEventType::TransactionCreated => "created",
+        WebhookEventType::TransactionSettled => "settled",
+        WebhookEventType::TransactionDeleted => "deleted",
+        WebhookEventType::Ping => "ping",
+    };
+
+    WebhookEventResource {
+        resource_type: "webhook-events".to_string(),
+        id: format!("{}-{}-{}", POLLER_WEBHOOK_ID, kind, transaction_id),
+        attributes: WebhookEventAttributes {
+            event_type,
+            created_at: now.to_rfc3339(),
+        },
+        relationships: WebhookEventRelationships {
+            webhook: Relationship {
+                data: ResourceIdentifier::new("webhooks", POLLER_WEBHOOK_ID),
+                links: None,
+            },
+            transaction: Some(Relationship {
+                data: ResourceIdentifier::new("transactions", transaction_id),
+                links: None,
+            }),
+        },
+    }
+}
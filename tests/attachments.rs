@@ -0,0 +1,195 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::{Local, TimeDelta};
+use common::attachment;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use uprusty::{
+    AttachmentResource, AttachmentsExt, Client, ClientError, HttpRequest, HttpResponse, Transport,
+};
+
+const FILE: &[u8] = b"%PDF-1.4 receipt";
+
+/// Serves a freshly signed copy of the attachment from the API, and its file from
+/// `files.example`. Files requested with a signature in `rejected` get a 403.
+#[derive(Debug)]
+struct StubApi {
+    fresh: Mutex<Value>,
+    rejected: Mutex<Vec<&'static str>>,
+    content_type: &'static str,
+    refetches: AtomicUsize,
+    downloads: Mutex<Vec<String>>,
+}
+
+impl StubApi {
+    fn new() -> Self {
+        let mut fresh = attachment("receipt", "coffee");
+        fresh["attributes"]["fileURL"] =
+            json!("https://files.example/receipt.pdf?X-Amz-Signature=fresh");
+        Self {
+            fresh: Mutex::new(fresh),
+            rejected: Mutex::new(Vec::new()),
+            content_type: "application/pdf",
+            refetches: AtomicUsize::new(0),
+            downloads: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn content_type(mut self, content_type: &'static str) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    fn reject(self, signature: &'static str) -> Self {
+        self.rejected.lock().unwrap().push(signature);
+        self
+    }
+
+    fn refetches(&self) -> usize {
+        self.refetches.load(Ordering::SeqCst)
+    }
+
+    fn downloads(&self) -> Vec<String> {
+        self.downloads.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        if request.url.host_str() == Some("api.up.com.au") {
+            assert_eq!(request.url.path(), "/api/v1/attachments/receipt");
+            self.refetches.fetch_add(1, Ordering::SeqCst);
+            let body = serde_json::to_vec(&json!({ "data": *self.fresh.lock().unwrap() }))?;
+            return Ok(HttpResponse::new(StatusCode::OK, HeaderMap::new(), body));
+        }
+
+        // Pre-signed file URLs must not carry the bearer token.
+        assert!(request.headers.get("Authorization").is_none());
+        let signature = request
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == "X-Amz-Signature")
+            .map(|(_, value)| value.into_owned())
+            .unwrap_or_default();
+        self.downloads.lock().unwrap().push(signature.clone());
+        if self.rejected.lock().unwrap().contains(&signature.as_str()) {
+            return Ok(HttpResponse::new(
+                StatusCode::FORBIDDEN,
+                HeaderMap::new(),
+                Vec::new(),
+            ));
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+        Ok(HttpResponse::new(StatusCode::OK, headers, FILE.to_vec()))
+    }
+}
+
+fn expiring_in(expires_in: TimeDelta) -> AttachmentResource {
+    let mut json = attachment("receipt", "coffee");
+    json["attributes"]["fileURLExpiresAt"] =
+        json!((Local::now().fixed_offset() + expires_in).to_rfc3339());
+    serde_json::from_value(json).unwrap()
+}
+
+async fn download(
+    api: &Arc<StubApi>,
+    attachment: &AttachmentResource,
+) -> Result<Vec<u8>, ClientError> {
+    let client = Client::with_transport("up:yeah:test", Arc::clone(api)).unwrap();
+    let mut file = Vec::new();
+    let written = client
+        .download_attachment_resource(attachment, &mut file)
+        .await?;
+    assert_eq!(written, file.len() as u64);
+    Ok(file)
+}
+
+#[tokio::test]
+async fn unexpired_url_is_used_as_is() {
+    let api = Arc::new(StubApi::new());
+    let file = download(&api, &expiring_in(TimeDelta::minutes(5)))
+        .await
+        .unwrap();
+
+    assert_eq!(file, FILE);
+    assert_eq!(api.refetches(), 0);
+    assert_eq!(api.downloads(), ["first"]);
+}
+
+#[tokio::test]
+async fn url_expiring_within_the_margin_is_refetched() {
+    let api = Arc::new(StubApi::new());
+    let file = download(&api, &expiring_in(TimeDelta::seconds(10)))
+        .await
+        .unwrap();
+
+    assert_eq!(file, FILE);
+    assert_eq!(api.refetches(), 1);
+    assert_eq!(api.downloads(), ["fresh"]);
+}
+
+#[tokio::test]
+async fn rejected_url_is_retried_once_with_a_fresh_one() {
+    let api = Arc::new(StubApi::new().reject("first"));
+    let file = download(&api, &expiring_in(TimeDelta::minutes(5)))
+        .await
+        .unwrap();
+    assert_eq!(file, FILE);
+    assert_eq!(api.refetches(), 1);
+    assert_eq!(api.downloads(), ["first", "fresh"]);
+
+    // A second rejection is an error.
+    let api = Arc::new(StubApi::new().reject("first").reject("fresh"));
+    let error = download(&api, &expiring_in(TimeDelta::minutes(5)))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::HttpStatus {
+            status: StatusCode::FORBIDDEN,
+            ..
+        }
+    ));
+    assert_eq!(api.downloads(), ["first", "fresh"]);
+}
+
+#[tokio::test]
+async fn content_type_must_match_the_attachment() {
+    let api = Arc::new(StubApi::new().content_type("text/html"));
+    let error = download(&api, &expiring_in(TimeDelta::minutes(5)))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::ContentTypeMismatch { expected, actual }
+            if expected == "application/pdf" && actual.as_deref() == Some("text/html")
+    ));
+
+    // Parameters and case do not matter.
+    let api = Arc::new(StubApi::new().content_type("Application/PDF; charset=binary"));
+    let file = download(&api, &expiring_in(TimeDelta::minutes(5)))
+        .await
+        .unwrap();
+    assert_eq!(file, FILE);
+}
+
+#[tokio::test]
+async fn attachment_without_a_file_url_is_refetched_before_failing() {
+    let api = Arc::new(StubApi::new());
+    api.fresh.lock().unwrap()["attributes"]["fileURL"] = Value::Null;
+    let mut json = attachment("receipt", "coffee");
+    json["attributes"]["fileURL"] = Value::Null;
+    let attachment: AttachmentResource = serde_json::from_value(json).unwrap();
+
+    let error = download(&api, &attachment).await.unwrap_err();
+    assert!(matches!(error, ClientError::MissingFileUrl));
+    assert_eq!(api.refetches(), 1);
+    assert!(api.downloads().is_empty());
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate};
+use common::{SAVER, SPENDING, account, transaction};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use uprusty::analysis::{
+    BalanceHistory, BalanceInconsistency, fetch_balance_history, reconstruct_balance_history,
+};
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::{
+    AccountResource, Client, ClientError, HttpRequest, HttpResponse, TransactionResource, Transport,
+};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn balance_on(history: &BalanceHistory, day: &str) -> i64 {
+    history.on(date(day)).unwrap().value_in_base_units
+}
+
+#[test]
+fn walks_back_from_the_current_balance() {
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 10_000);
+    let transactions = [
+        transaction("held", -500)
+            .created_at("2024-09-12T09:00:00+10:00")
+            .held()
+            .build(),
+        transaction("coffee", -2_000)
+            .created_at("2024-09-10T08:00:00+10:00")
+            .build(),
+        // Created on the 9th, settled on the 10th.
+        transaction("late", -1_000)
+            .created_at("2024-09-09T22:00:00+10:00")
+            .settled_at("2024-09-10T09:00:00+10:00")
+            .build(),
+        transaction("pay", 5_000)
+            .created_at("2024-09-05T09:00:00+10:00")
+            .build(),
+        transaction("other", 99_999)
+            .created_at("2024-09-06T09:00:00+10:00")
+            .account(SAVER)
+            .build(),
+    ];
+
+    let history = reconstruct_balance_history(&spending, &transactions);
+    assert!(history.is_consistent(), "{:?}", history.inconsistencies);
+
+    // The held amount is already taken out of the current balance, but not yet settled.
+    assert_eq!(balance_on(&history, "2024-09-12"), 10_500);
+    assert_eq!(balance_on(&history, "2024-09-10"), 10_500);
+    assert_eq!(balance_on(&history, "2024-09-09"), 13_500);
+    assert_eq!(balance_on(&history, "2024-09-05"), 13_500);
+    assert_eq!(history.days[0].date, date("2024-09-05"));
+    assert_eq!(history.opening_balance.value_in_base_units, 8_500);
+    assert!(history.on(date("2024-09-04")).is_none());
+
+    // One entry per day, through today.
+    for pair in history.days.windows(2) {
+        assert_eq!(pair[0].date.succ_opt().unwrap(), pair[1].date);
+    }
+    assert!(history.days.last().unwrap().date >= date("2024-09-12"));
+}
+
+#[test]
+fn negative_saver_balance_is_flagged() {
+    let saver = account(SAVER, "Holiday", "SAVER", 1_000);
+    let transactions = [
+        transaction("deposit", 5_000)
+            .created_at("2024-09-10T09:00:00+10:00")
+            .account(SAVER)
+            .build(),
+        transaction("earlier", 1_000)
+            .created_at("2024-09-08T09:00:00+10:00")
+            .account(SAVER)
+            .build(),
+    ];
+
+    let history = reconstruct_balance_history(&saver, &transactions);
+    let negative: Vec<_> = history
+        .inconsistencies
+        .iter()
+        .map(|inconsistency| match inconsistency {
+            BalanceInconsistency::NegativeSaverBalance { date, balance } => {
+                (date.to_string(), balance.value_in_base_units)
+            }
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        negative,
+        [
+            ("2024-09-08".to_string(), -4_000),
+            ("2024-09-09".to_string(), -4_000)
+        ]
+    );
+
+    // A transactional account may go negative.
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 1_000);
+    let transactions = [transaction("deposit", 5_000)
+        .created_at("2024-09-10T09:00:00+10:00")
+        .build()];
+    assert!(reconstruct_balance_history(&spending, &transactions).is_consistent());
+}
+
+#[test]
+fn opening_balance_is_checked_from_account_creation() {
+    // Account created on 2024-01-01.
+    let first = transaction("first", 5_000).created_at("2024-01-01T10:00:00+11:00");
+
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 5_000);
+    let history = reconstruct_balance_history(&spending, &[first.clone().build()]);
+    assert!(history.is_consistent(), "{:?}", history.inconsistencies);
+
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 7_000);
+    let history = reconstruct_balance_history(&spending, &[first.build()]);
+    assert!(matches!(
+        history.inconsistencies.as_slice(),
+        [BalanceInconsistency::UnexplainedOpeningBalance { opening_balance }]
+            if opening_balance.value_in_base_units == 2_000
+    ));
+
+    // A history starting after creation may open at any balance.
+    let later = transaction("later", 5_000)
+        .created_at("2024-03-01T10:00:00+11:00")
+        .build();
+    assert!(reconstruct_balance_history(&spending, &[later]).is_consistent());
+}
+
+#[test]
+fn bad_timestamps_are_flagged() {
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 0);
+    let mut unsettled = transaction("unsettled", -1_000)
+        .created_at("2024-09-10T09:00:00+10:00")
+        .json();
+    unsettled["attributes"]["settledAt"] = serde_json::Value::Null;
+    let mut garbled = transaction("garbled", -2_000).json();
+    garbled["attributes"]["settledAt"] = json!("yesterday");
+    let transactions: Vec<TransactionResource> = [unsettled, garbled]
+        .into_iter()
+        .map(|t| serde_json::from_value(t).unwrap())
+        .collect();
+
+    let history = reconstruct_balance_history(&spending, &transactions);
+    assert!(matches!(
+        history.inconsistencies.as_slice(),
+        [
+            BalanceInconsistency::MissingSettlementDate { transaction_id: missing },
+            BalanceInconsistency::InvalidTimestamp { transaction_id: invalid },
+        ] if missing == "unsettled" && invalid == "garbled"
+    ));
+    // The unsettled transaction counts on the day it was created; the garbled one not at all.
+    assert_eq!(history.days[0].date, date("2024-09-10"));
+    assert_eq!(balance_on(&history, "2024-09-10"), 0);
+    assert_eq!(history.opening_balance.value_in_base_units, 1_000);
+}
+
+/// Serves one account and its transactions, and remembers the `filter[since]` asked for.
+#[derive(Debug)]
+struct StubApi {
+    account: AccountResource,
+    transactions: Vec<TransactionResource>,
+    since: Mutex<Option<String>>,
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let body = if request.url.path().ends_with("/transactions") {
+            let since = request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "filter[since]")
+                .map(|(_, value)| value.into_owned());
+            // Like the API, select by creation time.
+            let since = since.map(|since| DateTime::parse_from_rfc3339(&since).unwrap());
+            let transactions: Vec<_> = self
+                .transactions
+                .iter()
+                .filter(|t| {
+                    let created_at = DateTime::parse_from_rfc3339(&t.attributes.created_at);
+                    since.is_none_or(|since| created_at.unwrap() >= since)
+                })
+                .collect();
+            *self.since.lock().unwrap() = since.map(|since| since.to_rfc3339());
+            json!({ "data": transactions, "links": { "prev": null, "next": null } })
+        } else {
+            json!({ "data": self.account })
+        };
+        Ok(HttpResponse::new(
+            StatusCode::OK,
+            HeaderMap::new(),
+            serde_json::to_vec(&body)?,
+        ))
+    }
+}
+
+#[tokio::test]
+async fn fetched_history_includes_transactions_created_before_since() {
+    let stub = Arc::new(StubApi {
+        account: account(SPENDING, "Spending", "TRANSACTIONAL", 10_000),
+        transactions: vec![
+            transaction("after", -2_000)
+                .created_at("2024-09-06T09:00:00+10:00")
+                .build(),
+            // Created before `since`, settled after it.
+            transaction("straddling", -1_000)
+                .created_at("2024-09-03T21:00:00+10:00")
+                .settled_at("2024-09-05T09:00:00+10:00")
+                .build(),
+            transaction("before", 3_000)
+                .created_at("2024-09-02T09:00:00+10:00")
+                .build(),
+        ],
+        since: Mutex::new(None),
+    });
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&stub)).unwrap();
+
+    let history = fetch_balance_history(&client, SPENDING, Some("2024-09-04T00:00:00+10:00"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stub.since.lock().unwrap().as_deref(),
+        Some("2024-08-05T00:00:00+10:00")
+    );
+    assert!(history.is_consistent(), "{:?}", history.inconsistencies);
+    assert_eq!(history.days[0].date, date("2024-09-04"));
+    assert_eq!(history.opening_balance.value_in_base_units, 13_000);
+    assert_eq!(balance_on(&history, "2024-09-04"), 13_000);
+    assert_eq!(balance_on(&history, "2024-09-05"), 12_000);
+    assert_eq!(balance_on(&history, "2024-09-06"), 10_000);
+}
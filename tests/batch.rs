@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::{
+    BatchExt, BatchOptions, BatchOutcome, Client, ClientError, HttpRequest, HttpResponse, Transport,
+};
+
+/// Accepts every request after a short delay, except for transaction `fail`, and records
+/// how many requests were in flight at once.
+#[derive(Debug, Default)]
+struct SlowApi {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    requests: AtomicUsize,
+}
+
+#[async_trait]
+impl Transport for SlowApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let status = if request.url.path().contains("/transactions/fail/") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::NO_CONTENT
+        };
+        Ok(HttpResponse::new(status, HeaderMap::new(), Vec::new()))
+    }
+}
+
+#[tokio::test]
+async fn batch_runs_at_most_concurrency_requests_and_keeps_order() {
+    let api = Arc::new(SlowApi::default());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+    let ids: Vec<String> = (0..40)
+        .map(|n| {
+            if n == 17 {
+                "fail".to_string()
+            } else {
+                n.to_string()
+            }
+        })
+        .collect();
+    let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+
+    let report = client
+        .remove_tags_from_transactions(&ids, &["Holiday"], BatchOptions::with_concurrency(3))
+        .await;
+
+    assert_eq!(api.requests.load(Ordering::SeqCst), 40);
+    assert!(api.max_in_flight.load(Ordering::SeqCst) <= 3);
+    let order: Vec<&str> = report
+        .results
+        .iter()
+        .map(|r| r.transaction_id.as_str())
+        .collect();
+    assert_eq!(order, ids);
+    assert_eq!(report.succeeded().count(), 39);
+    let failed: Vec<&str> = report.failed().map(|(id, _)| id).collect();
+    assert_eq!(failed, ["fail"]);
+    assert!(matches!(
+        report.results[17].outcome,
+        BatchOutcome::Failed(_)
+    ));
+}
+
+#[tokio::test]
+async fn zero_concurrency_still_runs_the_batch() {
+    let api = Arc::new(SlowApi::default());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+
+    let report = client
+        .add_tags_to_transactions(&["a", "b"], &["Holiday"], BatchOptions::with_concurrency(0))
+        .await;
+
+    assert_eq!(report.succeeded().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(api.max_in_flight.load(Ordering::SeqCst), 1);
+}
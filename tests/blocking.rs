@@ -0,0 +1,121 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{SAVER, SPENDING, account, transaction};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use uprusty::blocking::Client;
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::{ClientError, HttpRequest, HttpResponse, Transport};
+
+const API: &str = "https://api.up.com.au/api/v1";
+
+/// Serves two accounts and two pages of transactions, and records every URL requested.
+#[derive(Debug, Default)]
+struct StubApi {
+    requests: Mutex<Vec<String>>,
+}
+
+fn ok(body: Value) -> Result<HttpResponse, ClientError> {
+    Ok(HttpResponse::new(
+        StatusCode::OK,
+        HeaderMap::new(),
+        serde_json::to_vec(&body)?,
+    ))
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let url = request.url.as_str().to_string();
+        self.requests.lock().unwrap().push(url.clone());
+
+        let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 10_000);
+        let saver = account(SAVER, "Holiday", "SAVER", 50_000);
+        let path = request.url.path().trim_start_matches("/api/v1/");
+        match path {
+            "accounts" => ok(json!({
+                "data": [spending, saver],
+                "links": { "prev": null, "next": null },
+            })),
+            "transactions" if request.url.query() == Some("page[after]=b") => ok(json!({
+                "data": [transaction("c", -300).build()],
+                "links": { "prev": format!("{}/transactions?page[before]=c", API), "next": null },
+            })),
+            "transactions" => ok(json!({
+                "data": [transaction("a", -100).build(), transaction("b", -200).build()],
+                "links": { "prev": null, "next": format!("{}/transactions?page[after]=b", API) },
+            })),
+            _ if path == format!("accounts/{}", SPENDING) => ok(json!({ "data": spending })),
+            _ => Ok(HttpResponse::new(
+                StatusCode::NOT_FOUND,
+                HeaderMap::new(),
+                br#"{"errors":[{"status":"404","title":"Not Found"}]}"#.to_vec(),
+            )),
+        }
+    }
+}
+
+fn client() -> (Arc<StubApi>, Client) {
+    let api = Arc::new(StubApi::default());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+    (api, client)
+}
+
+#[test]
+fn blocking_calls_run_without_a_runtime() {
+    let (_, client) = client();
+
+    let accounts = client.list_accounts(None, None, None).unwrap();
+    let names: Vec<&str> = accounts
+        .data
+        .iter()
+        .map(|a| a.attributes.display_name.as_str())
+        .collect();
+    assert_eq!(names, ["Spending", "Holiday"]);
+
+    let account = client.get_account(SPENDING).unwrap();
+    assert_eq!(account.data.attributes.balance.value_in_base_units, 10_000);
+}
+
+#[test]
+fn blocking_client_follows_pages() {
+    let (api, client) = client();
+
+    let first = client.list_transactions(None, None).unwrap();
+    assert_eq!(first.data.len(), 2);
+    let next = first.links.next.as_deref().unwrap();
+    let second: uprusty::TransactionsResponse = client.get_page(next).unwrap();
+    let ids: Vec<&str> = second.data.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["c"]);
+
+    let all = client.list_all_transactions(None).unwrap();
+    let ids: Vec<&str> = all.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+    assert_eq!(api.requests.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn blocking_client_returns_api_errors() {
+    let (api, client) = client();
+
+    let error = client.get_account("missing").unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::HttpStatus {
+            status: StatusCode::NOT_FOUND,
+            ..
+        }
+    ));
+
+    // Links to other hosts are refused before any request is made.
+    let error = client
+        .get_page::<uprusty::TransactionsResponse>("https://example.com/transactions")
+        .unwrap_err();
+    assert!(matches!(error, ClientError::ForeignLink(_)));
+    assert_eq!(
+        *api.requests.lock().unwrap(),
+        [format!("{}/accounts/missing", API)]
+    );
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use common::transaction;
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use uprusty::analysis::{Budget, BudgetMonitor, BudgetTracker, BudgetWebhookHandler};
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::webhook::WebhookEventHandler;
+use uprusty::{Client, ClientError, HttpRequest, HttpResponse, Transport, WebhookEventResource};
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 9, 15).unwrap()
+}
+
+fn monitor() -> BudgetMonitor {
+    let tracker = BudgetTracker::new()
+        .budget(Budget::category("groceries", 10_000))
+        .budget(Budget::category("takeaway", 5_000))
+        .budget(Budget::parent_category("good-life", 20_000));
+    BudgetMonitor::new(tracker, &[], today())
+}
+
+fn spent(monitor: &BudgetMonitor) -> Vec<i64> {
+    monitor
+        .spent()
+        .into_iter()
+        .map(|(_, spent)| spent)
+        .collect()
+}
+
+#[test]
+fn recategorized_transaction_moves_between_budgets() {
+    let monitor = monitor();
+    monitor.observe(
+        &transaction("a", -1_000)
+            .category(Some("groceries"), Some("home"))
+            .build(),
+    );
+    assert_eq!(spent(&monitor), [1_000, 0, 0]);
+
+    monitor.observe(
+        &transaction("a", -1_000)
+            .category(Some("takeaway"), Some("good-life"))
+            .build(),
+    );
+    assert_eq!(spent(&monitor), [0, 1_000, 1_000]);
+}
+
+#[test]
+fn uncategorized_transaction_counts_once_categorized() {
+    let monitor = monitor();
+    monitor.observe(&transaction("a", -1_000).build());
+    assert_eq!(spent(&monitor), [0, 0, 0]);
+
+    monitor.observe(
+        &transaction("a", -1_000)
+            .category(Some("groceries"), Some("home"))
+            .build(),
+    );
+    assert_eq!(spent(&monitor), [1_000, 0, 0]);
+}
+
+#[test]
+fn settled_amount_replaces_held_amount() {
+    let monitor = monitor();
+    let held = transaction("a", -4_500)
+        .category(Some("takeaway"), Some("good-life"))
+        .held()
+        .build();
+    let alerts = monitor.observe(&held);
+    assert_eq!(spent(&monitor), [0, 4_500, 4_500]);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].budget, "takeaway");
+    assert_eq!(alerts[0].threshold, 0.8);
+
+    let settled = transaction("a", -5_200)
+        .category(Some("takeaway"), Some("good-life"))
+        .settled_from_hold(-4_500)
+        .build();
+    let alerts = monitor.observe(&settled);
+    assert_eq!(spent(&monitor), [0, 5_200, 5_200]);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].threshold, 1.0);
+
+    // Seeing the same settled transaction again changes nothing.
+    assert!(monitor.observe(&settled).is_empty());
+    assert_eq!(spent(&monitor), [0, 5_200, 5_200]);
+}
+
+#[test]
+fn removed_transaction_stops_counting() {
+    let monitor = monitor();
+    monitor.observe(
+        &transaction("a", -1_000)
+            .category(Some("groceries"), Some("home"))
+            .held()
+            .build(),
+    );
+    monitor.observe(
+        &transaction("b", -2_000)
+            .category(Some("groceries"), Some("home"))
+            .build(),
+    );
+    monitor.remove("a");
+    assert_eq!(spent(&monitor), [2_000, 0, 0]);
+
+    // Unknown transactions are ignored.
+    monitor.remove("c");
+    assert_eq!(spent(&monitor), [2_000, 0, 0]);
+}
+
+fn event(event_type: &str, transaction_id: &str) -> WebhookEventResource {
+    serde_json::from_value(serde_json::json!({
+        "type": "webhook-events",
+        "id": "event",
+        "attributes": {
+            "eventType": event_type,
+            "createdAt": "2024-09-15T12:00:00+10:00",
+        },
+        "relationships": {
+            "webhook": { "data": { "type": "webhooks", "id": "webhook" } },
+            "transaction": { "data": { "type": "transactions", "id": transaction_id } },
+        },
+    }))
+    .unwrap()
+}
+
+/// Answers every request with the same transaction.
+#[derive(Debug)]
+struct OneTransaction(serde_json::Value);
+
+#[async_trait]
+impl Transport for OneTransaction {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let body = serde_json::to_vec(&serde_json::json!({ "data": self.0 }))?;
+        Ok(HttpResponse::new(StatusCode::OK, HeaderMap::new(), body))
+    }
+}
+
+#[test]
+fn webhook_handler_fetches_on_the_given_runtime() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let monitor = Arc::new(monitor());
+    let transaction = transaction("a", -9_000)
+        .category(Some("groceries"), Some("home"))
+        .json();
+    let client = Client::with_transport("up:yeah:test", OneTransaction(transaction)).unwrap();
+
+    // Created outside the runtime.
+    let (alerts, received) = mpsc::channel();
+    let handler = BudgetWebhookHandler::new(
+        Arc::clone(&monitor),
+        client,
+        runtime.handle().clone(),
+        move |alert| alerts.send(alert).unwrap(),
+    );
+    handler.on_transaction_created("a", &event("TRANSACTION_CREATED", "a"));
+
+    let alert = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(alert.budget, "groceries");
+    assert_eq!(alert.threshold, 0.8);
+    assert_eq!(spent(&monitor), [9_000, 0, 0]);
+}
+
+#[tokio::test]
+async fn webhook_deletion_releases_hold() {
+    let monitor = Arc::new(monitor());
+    monitor.observe(
+        &transaction("a", -1_000)
+            .category(Some("groceries"), Some("home"))
+            .held()
+            .build(),
+    );
+
+    let client = Client::new("up:yeah:test").unwrap();
+    let handler =
+        BudgetWebhookHandler::new(Arc::clone(&monitor), client, Handle::current(), |_| {});
+    handler.on_transaction_deleted("a", &event("TRANSACTION_DELETED", "a"));
+
+    assert_eq!(spent(&monitor), [0, 0, 0]);
+}
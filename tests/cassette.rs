@@ -0,0 +1,217 @@
+//! Record traffic from a stub API into a cassette, check what the cassette leaks, and
+//! replay it.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use uprusty::prelude::*;
+use uprusty::transport::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use uprusty::transport::{Cassette, RecordingTransport, ReplayTransport, StatusCode};
+use uprusty::{
+    AccountResponse, AccountsResponse, AttachmentResponse, ClientError, HttpRequest, HttpResponse,
+    TransactionResponse, Transport,
+};
+
+const TOKEN: &str = "up:yeah:secret-token";
+const SPENDING: &str = "2e5dbb62-0c63-4c76-b4b1-1a9b8c6f8d0e";
+const ACCOUNT_IDS: &[&str] = &[
+    SPENDING,
+    "7c8a1d4f-5e23-4b9a-9f61-3d2e8a7b6c5d",
+    "a3f0c9e2-6b1d-4e8f-8c7a-5b4d3e2f1a0b",
+];
+const TRANSACTION: &str = "4b7d2e1a-9c3f-4a8e-b6d5-0f1e2d3c4b5a";
+const FILE: &[u8] = b"%PDF-1.4 receipt";
+
+fn fixture(name: &str) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/models")
+        .join(name);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn cassette_path(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/cassettes")
+        .join(name)
+}
+
+/// Serves fixtures by URL, and fails requests without the bearer token.
+#[derive(Debug)]
+struct StubApi {
+    responses: HashMap<String, (&'static str, Vec<u8>)>,
+}
+
+impl StubApi {
+    fn new() -> Self {
+        let api = "https://api.up.com.au/api/v1";
+        let mut attachment = fixture("attachment.json");
+        attachment["data"]["attributes"]["fileURLExpiresAt"] = "2099-01-01T00:00:00+10:00".into();
+        let file_url = attachment["data"]["attributes"]["fileURL"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let json = |value: Value| ("application/json", serde_json::to_vec(&value).unwrap());
+        let responses = HashMap::from([
+            (format!("{}/accounts", api), json(fixture("accounts.json"))),
+            (
+                format!("{}/accounts/{}", api, SPENDING),
+                json(fixture("account.json")),
+            ),
+            (
+                format!("{}/transactions/{}", api, TRANSACTION),
+                json(fixture("transaction.json")),
+            ),
+            (file_url, ("application/pdf", FILE.to_vec())),
+        ]);
+        Self { responses }
+    }
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let is_api = request.url.host_str() == Some("api.up.com.au");
+        let authorized = request
+            .headers
+            .get("Authorization")
+            .is_some_and(|v| v == format!("Bearer {}", TOKEN).as_str());
+        let mut url = request.url.clone();
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        let response = self.responses.get(url.as_str());
+
+        let (status, content_type, body) = match response {
+            Some(_) if is_api && !authorized => (StatusCode::UNAUTHORIZED, "text/plain", vec![]),
+            Some((content_type, body)) => (StatusCode::OK, *content_type, body.clone()),
+            None => (StatusCode::NOT_FOUND, "text/plain", vec![]),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        Ok(HttpResponse::new(status, headers, body))
+    }
+}
+
+struct Responses {
+    accounts: Value,
+    account: Value,
+    transaction: Value,
+    file: Vec<u8>,
+}
+
+async fn exercise(client: &Client, account_id: &str) -> Result<Responses, ClientError> {
+    let accounts = client.list_accounts(None, None, None).await?;
+    let account = client.get_account(account_id).await?;
+    let transaction = client.get_transaction(TRANSACTION).await?;
+
+    let attachment: AttachmentResponse = serde_json::from_value(recorded_attachment()).unwrap();
+    let mut file = Vec::new();
+    client
+        .download_attachment_resource(&attachment.data, &mut file)
+        .await?;
+
+    Ok(Responses {
+        accounts: serde_json::to_value(accounts)?,
+        account: serde_json::to_value(account)?,
+        transaction: serde_json::to_value(transaction)?,
+        file,
+    })
+}
+
+// The attachment as a caller would hold it: fetched earlier, with its file URL redacted
+// once it went through a cassette.
+fn recorded_attachment() -> Value {
+    let mut attachment = fixture("attachment.json");
+    attachment["data"]["attributes"]["fileURLExpiresAt"] = "2099-01-01T00:00:00+10:00".into();
+    attachment
+}
+
+// A recorded response body, as the model for `T` serializes it.
+fn recorded_json<T: DeserializeOwned + Serialize>(cassette: &Cassette, index: usize) -> Value {
+    let body = cassette.interactions[index]
+        .response
+        .body
+        .to_bytes()
+        .unwrap();
+    serde_json::to_value(serde_json::from_slice::<T>(&body).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn recorded_cassette_is_redacted_and_replays() {
+    let recorder = Arc::new(RecordingTransport::with_transport(StubApi::new()));
+    let client = Client::with_transport(TOKEN, Arc::clone(&recorder)).unwrap();
+    let recorded = exercise(&client, SPENDING).await.unwrap();
+    assert_eq!(recorded.file, FILE);
+
+    let cassette = recorder.cassette().unwrap();
+    assert_eq!(cassette.interactions.len(), 4);
+    let saved = serde_json::to_string(&cassette).unwrap();
+    assert!(!saved.contains("Authorization"));
+    assert!(!saved.contains(TOKEN));
+    for account_id in ACCOUNT_IDS {
+        assert!(!saved.contains(account_id), "{} was recorded", account_id);
+    }
+    assert!(!saved.contains("X-Amz-Signature"));
+    assert!(!saved.contains("X-Amz-Expires"));
+    assert!(saved.contains("redacted-account-1"));
+
+    // Saving and loading keeps every interaction.
+    let path = std::env::temp_dir().join(format!("uprusty-cassette-{}.json", std::process::id()));
+    cassette.save(&path).unwrap();
+    let loaded = Cassette::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), saved);
+
+    let replay = Arc::new(ReplayTransport::new(loaded));
+    let client = Client::with_transport("up:yeah:replay", Arc::clone(&replay)).unwrap();
+    let account_id = cassette.interactions[1]
+        .request
+        .url
+        .rsplit('/')
+        .next()
+        .unwrap()
+        .to_string();
+    assert!(!replay.is_exhausted());
+    let replayed = exercise(&client, &account_id).await.unwrap();
+
+    assert_eq!(
+        replayed.accounts,
+        recorded_json::<AccountsResponse>(&cassette, 0)
+    );
+    assert_eq!(
+        replayed.account,
+        recorded_json::<AccountResponse>(&cassette, 1)
+    );
+    assert_eq!(
+        replayed.transaction,
+        recorded_json::<TransactionResponse>(&cassette, 2)
+    );
+    assert_eq!(replayed.file, FILE);
+    assert!(replay.is_exhausted());
+
+    // Every interaction has been used, so a repeated request has nothing to replay.
+    let repeated = client.list_accounts(None, None, None).await;
+    assert!(matches!(repeated, Err(ClientError::Transport(_))));
+}
+
+#[tokio::test]
+async fn replay_matches_method_and_url() {
+    let replay = ReplayTransport::load(cassette_path("accounts.json")).unwrap();
+    let client = Client::with_transport("up:yeah:replay", replay).unwrap();
+
+    let error = client.get_account("unknown").await.unwrap_err();
+    assert!(matches!(error, ClientError::Transport(_)));
+
+    let accounts = client.list_accounts(None, None, None).await.unwrap();
+    assert_eq!(accounts.data.len(), 3);
+    assert_eq!(accounts.data[0].id, "redacted-account-1");
+
+    let account = client.get_account("redacted-account-1").await.unwrap();
+    assert_eq!(account.data.id, "redacted-account-1");
+}
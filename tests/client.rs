@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use serde_json::json;
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::{Client, ClientError, HttpRequest, HttpResponse, Transport};
+
+/// Answers every request with `status` and a JSON body, like the API does for errors.
+#[derive(Debug)]
+struct Respond(StatusCode);
+
+#[async_trait]
+impl Transport for Respond {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let body = if self.0.is_success() {
+            json!({ "meta": { "id": "ping-id", "statusEmoji": "⚡️" } })
+        } else {
+            json!({ "errors": [{ "status": "401", "title": "Not Authorized" }] })
+        };
+        Ok(HttpResponse::new(
+            self.0,
+            HeaderMap::new(),
+            serde_json::to_vec(&body)?,
+        ))
+    }
+}
+
+#[tokio::test]
+async fn ping_returns_the_meta_block() {
+    let client = Client::with_transport("up:yeah:test", Respond(StatusCode::OK)).unwrap();
+    let ping = client.ping().await.unwrap();
+    assert_eq!(ping["meta"]["id"], "ping-id");
+}
+
+#[tokio::test]
+async fn ping_fails_for_an_invalid_token() {
+    let client = Client::with_transport("up:yeah:test", Respond(StatusCode::UNAUTHORIZED)).unwrap();
+    let error = client.ping().await.unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::HttpStatus {
+            status: StatusCode::UNAUTHORIZED,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,222 @@
+//! Builders for the payloads integration tests feed into the library.
+
+#![allow(dead_code)]
+
+use serde_json::{Value, json};
+use uprusty::{AccountResource, TransactionResource};
+
+pub const SPENDING: &str = "2e5dbb62-0c63-4c76-b4b1-1a9b8c6f8d0e";
+pub const SAVER: &str = "7c8a1d4f-5e23-4b9a-9f61-3d2e8a7b6c5d";
+
+fn money(currency: &str, value_in_base_units: i64, decimal_places: u32) -> Value {
+    json!({
+        "currencyCode": currency,
+        "value": uprusty::format_base_units(value_in_base_units, decimal_places),
+        "valueInBaseUnits": value_in_base_units,
+    })
+}
+
+fn identifier(resource_type: &str, id: Option<&str>) -> Value {
+    match id {
+        Some(id) => json!({ "type": resource_type, "id": id }),
+        None => Value::Null,
+    }
+}
+
+/// An AUD account of `account_type` (`"SAVER"`, `"TRANSACTIONAL"` or `"HOME_LOAN"`).
+pub fn account(
+    id: &str,
+    display_name: &str,
+    account_type: &str,
+    balance_in_base_units: i64,
+) -> AccountResource {
+    serde_json::from_value(json!({
+        "type": "accounts",
+        "id": id,
+        "attributes": {
+            "displayName": display_name,
+            "accountType": account_type,
+            "ownershipType": "INDIVIDUAL",
+            "balance": money("AUD", balance_in_base_units, 2),
+            "createdAt": "2024-01-01T09:00:00+11:00",
+        },
+        "relationships": { "transactions": { "links": null } },
+        "links": { "self": format!("https://api.up.com.au/api/v1/accounts/{}", id) },
+    }))
+    .expect("valid account")
+}
+
+/// A PDF attachment of `transaction_id`, served from `https://files.example/<id>.pdf` and
+/// valid for the foreseeable future.
+pub fn attachment(id: &str, transaction_id: &str) -> Value {
+    json!({
+        "type": "attachments",
+        "id": id,
+        "attributes": {
+            "createdAt": "2024-09-10T12:00:00+10:00",
+            "fileURL": format!("https://files.example/{}.pdf?X-Amz-Signature=first", id),
+            "fileURLExpiresAt": "2099-01-01T00:00:00+10:00",
+            "fileExtension": "pdf",
+            "fileContentType": "application/pdf",
+        },
+        "relationships": {
+            "transaction": { "data": identifier("transactions", Some(transaction_id)) },
+        },
+    })
+}
+
+/// A settled AUD transaction on the spending account, adjusted with the builder methods.
+pub fn transaction(id: &str, amount_in_base_units: i64) -> TransactionBuilder {
+    TransactionBuilder {
+        json: json!({
+            "type": "transactions",
+            "id": id,
+            "attributes": {
+                "status": "SETTLED",
+                "rawText": null,
+                "description": format!("Transaction {}", id),
+                "message": null,
+                "isCategorizable": true,
+                "holdInfo": null,
+                "roundUp": null,
+                "cashback": null,
+                "amount": money("AUD", amount_in_base_units, 2),
+                "foreignAmount": null,
+                "cardPurchaseMethod": null,
+                "settledAt": "2024-09-10T12:00:00+10:00",
+                "createdAt": "2024-09-10T12:00:00+10:00",
+                "transactionType": null,
+                "note": null,
+                "performingCustomer": null,
+                "deepLinkURL": format!("up://transaction/{}", id),
+            },
+            "relationships": {
+                "account": { "data": identifier("accounts", Some(SPENDING)) },
+                "transferAccount": { "data": null },
+                "category": { "data": null },
+                "parentCategory": { "data": null },
+                "tags": { "data": [] },
+                "attachment": { "data": null },
+            },
+        }),
+    }
+}
+
+#[derive(Clone)]
+pub struct TransactionBuilder {
+    json: Value,
+}
+
+impl TransactionBuilder {
+    fn attributes(&mut self) -> &mut Value {
+        &mut self.json["attributes"]
+    }
+
+    fn relationships(&mut self) -> &mut Value {
+        &mut self.json["relationships"]
+    }
+
+    /// Held, with the same amount as the hold.
+    pub fn held(mut self) -> Self {
+        let amount = self.attributes()["amount"].clone();
+        let attributes = self.attributes();
+        attributes["status"] = json!("HELD");
+        attributes["settledAt"] = Value::Null;
+        attributes["holdInfo"] = json!({ "amount": amount, "foreignAmount": null });
+        self
+    }
+
+    /// Settled, after being held for `held_in_base_units`.
+    pub fn settled_from_hold(mut self, held_in_base_units: i64) -> Self {
+        self.attributes()["holdInfo"] = json!({
+            "amount": money("AUD", held_in_base_units, 2),
+            "foreignAmount": null,
+        });
+        self
+    }
+
+    /// The foreign amount of the hold, for a transaction made with `held` or
+    /// `settled_from_hold`.
+    pub fn held_foreign(
+        mut self,
+        currency: &str,
+        value_in_base_units: i64,
+        decimal_places: u32,
+    ) -> Self {
+        self.attributes()["holdInfo"]["foreignAmount"] =
+            money(currency, value_in_base_units, decimal_places);
+        self
+    }
+
+    /// Rounded up by `amount_in_base_units` (negative, as Up reports it), of which
+    /// `boost_in_base_units` was boosted.
+    pub fn round_up(mut self, amount_in_base_units: i64, boost_in_base_units: Option<i64>) -> Self {
+        self.attributes()["roundUp"] = json!({
+            "amount": money("AUD", amount_in_base_units, 2),
+            "boostPortion": boost_in_base_units.map(|boost| money("AUD", boost, 2)),
+        });
+        self
+    }
+
+    pub fn created_at(mut self, created_at: &str) -> Self {
+        self.attributes()["createdAt"] = json!(created_at);
+        if !self.attributes()["settledAt"].is_null() {
+            self.attributes()["settledAt"] = json!(created_at);
+        }
+        self
+    }
+
+    pub fn settled_at(mut self, settled_at: &str) -> Self {
+        self.attributes()["settledAt"] = json!(settled_at);
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.attributes()["description"] = json!(description);
+        self
+    }
+
+    pub fn foreign(
+        mut self,
+        currency: &str,
+        value_in_base_units: i64,
+        decimal_places: u32,
+    ) -> Self {
+        self.attributes()["foreignAmount"] = money(currency, value_in_base_units, decimal_places);
+        self
+    }
+
+    pub fn category(mut self, category: Option<&str>, parent: Option<&str>) -> Self {
+        let relationships = self.relationships();
+        relationships["category"]["data"] = identifier("categories", category);
+        relationships["parentCategory"]["data"] = identifier("categories", parent);
+        self
+    }
+
+    pub fn account(mut self, account_id: &str) -> Self {
+        self.relationships()["account"]["data"] = identifier("accounts", Some(account_id));
+        self
+    }
+
+    pub fn transfer_account(mut self, account_id: &str) -> Self {
+        self.relationships()["transferAccount"]["data"] = identifier("accounts", Some(account_id));
+        self
+    }
+
+    pub fn tags(mut self, tag_ids: &[&str]) -> Self {
+        let tags: Vec<Value> = tag_ids
+            .iter()
+            .map(|id| identifier("tags", Some(id)))
+            .collect();
+        self.relationships()["tags"]["data"] = Value::Array(tags);
+        self
+    }
+
+    pub fn json(self) -> Value {
+        self.json
+    }
+
+    pub fn build(self) -> TransactionResource {
+        serde_json::from_value(self.json).expect("valid transaction")
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{SPENDING, account, transaction};
+use uprusty::export::{OfxExporter, QifExporter, TransactionColumn, TransactionCsvWriter, csv_row};
+
+fn ofx(exporter: OfxExporter<'_>) -> String {
+    let mut out = Vec::new();
+    exporter
+        .write(&mut out, &[transaction("a", -1_000).build()])
+        .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn acctid(ofx: &str) -> &str {
+    let start = ofx.find("<ACCTID>").unwrap() + "<ACCTID>".len();
+    let end = ofx.find("</ACCTID>").unwrap();
+    &ofx[start..end]
+}
+
+#[test]
+fn ofx_account_id_fits_22_characters() {
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 0);
+
+    let id = acctid(&ofx(OfxExporter::new(&spending))).to_string();
+    assert_eq!(id, "2e5dbb620c634c76b4b11a");
+    assert!(id.len() <= 22);
+
+    let custom = ofx(OfxExporter::new(&spending).account_id("up-spending"));
+    assert_eq!(acctid(&custom), "up-spending");
+
+    let long = ofx(OfxExporter::new(&spending).account_id("an-account-id-longer-than-allowed"));
+    assert_eq!(acctid(&long), "an-account-id-longer-t");
+}
+
+#[test]
+fn qif_marks_settled_transactions_cleared() {
+    let spending = account(SPENDING, "Spending", "TRANSACTIONAL", 0);
+    let transactions = [
+        transaction("settled", -1_000).build(),
+        transaction("held", -2_000).held().build(),
+    ];
+
+    let mut out = Vec::new();
+    QifExporter::new(&spending)
+        .include_held(true)
+        .write(&mut out, &transactions)
+        .unwrap();
+    let qif = String::from_utf8(out).unwrap();
+
+    let entries: Vec<Vec<&str>> = qif
+        .split("^\n")
+        .map(|entry| entry.lines().collect::<Vec<_>>())
+        .filter(|lines| lines.iter().any(|line| line.starts_with('D')))
+        .collect();
+    assert_eq!(entries.len(), 2);
+    let cleared: Vec<Option<&&str>> = entries
+        .iter()
+        .map(|lines| lines.iter().find(|line| line.starts_with('C')))
+        .collect();
+    assert_eq!(cleared, [Some(&"C*"), None]);
+}
+
+#[test]
+fn csv_row_quotes_fields_that_need_it() {
+    assert_eq!(
+        csv_row(["plain", "a,b", "say \"hi\"", "two\nlines", ""]),
+        "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\n"
+    );
+}
+
+#[test]
+fn transaction_csv_has_a_header_and_one_escaped_row_per_transaction() {
+    let header = "id,account,status,created_at,settled_at,description,raw_text,message,amount,\
+                  currency,foreign_amount,foreign_currency,category,parent_category,tags,\
+                  card_method,card_suffix,round_up,round_up_boost,cashback,note\n";
+
+    let mut empty = TransactionCsvWriter::new(Vec::new());
+    empty.write_all([]).unwrap();
+    assert_eq!(String::from_utf8(empty.into_inner()).unwrap(), header);
+
+    let mut json = transaction("a", -1_550)
+        .description("Joe's \"Diner\", Sydney\nTable 4")
+        .foreign("USD", -1_000, 2)
+        .category(Some("restaurants-and-cafes"), Some("good-life"))
+        .tags(&["Holiday", "Food"])
+        .round_up(-50, Some(-25))
+        .json();
+    json["attributes"]["cardPurchaseMethod"] =
+        serde_json::json!({ "method": "CARD_ON_FILE", "cardNumberSuffix": "0042" });
+    let transaction: uprusty::TransactionResource = serde_json::from_value(json).unwrap();
+
+    let mut writer = TransactionCsvWriter::new(Vec::new());
+    writer.write_all([&transaction]).unwrap();
+    let csv = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(
+        csv,
+        format!(
+            "{}a,{},SETTLED,2024-09-10T12:00:00+10:00,2024-09-10T12:00:00+10:00,\
+             \"Joe's \"\"Diner\"\", Sydney\nTable 4\",,,-15.50,AUD,-10.00,USD,\
+             restaurants-and-cafes,good-life,Holiday;Food,CARD_ON_FILE,0042,-0.50,-0.25,,\n",
+            header, SPENDING
+        )
+    );
+
+    let mut columns = TransactionCsvWriter::with_columns(
+        Vec::new(),
+        vec![
+            TransactionColumn::Description,
+            TransactionColumn::ForeignAmount,
+        ],
+    );
+    columns.write(&transaction).unwrap();
+    assert_eq!(
+        String::from_utf8(columns.into_inner()).unwrap(),
+        "description,foreign_amount\n\"Joe's \"\"Diner\"\", Sydney\nTable 4\",-10.00\n"
+    );
+}
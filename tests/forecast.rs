@@ -0,0 +1,196 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{SAVER, SPENDING, account, transaction};
+use uprusty::TransactionResource;
+use uprusty::analysis::{CashflowForecaster, ForecastEventKind};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+/// `amount` on each date at 9am Brisbane time, on the spending account.
+fn series(id: &str, description: &str, amount: i64, dates: &[&str]) -> Vec<TransactionResource> {
+    dates
+        .iter()
+        .enumerate()
+        .map(|(n, day)| {
+            transaction(&format!("{}-{}", id, n), amount)
+                .description(description)
+                .created_at(&format!("{}T09:00:00+10:00", day))
+                .build()
+        })
+        .collect()
+}
+
+fn history() -> Vec<TransactionResource> {
+    let mut transactions = Vec::new();
+    transactions.extend(series(
+        "rent",
+        "RAY WHITE RENT",
+        -220_000,
+        &[
+            "2024-01-02",
+            "2024-02-02",
+            "2024-03-02",
+            "2024-04-02",
+            "2024-05-02",
+            "2024-06-02",
+        ],
+    ));
+    transactions.extend(series(
+        "salary",
+        "ACME PTY LTD",
+        350_000,
+        &[
+            "2024-04-11",
+            "2024-04-25",
+            "2024-05-09",
+            "2024-05-23",
+            "2024-06-06",
+            "2024-06-20",
+        ],
+    ));
+    // Due on 28 June and not yet paid.
+    transactions.extend(series(
+        "phone",
+        "TELSTRA",
+        -6_000,
+        &[
+            "2024-01-28",
+            "2024-02-28",
+            "2024-03-28",
+            "2024-04-28",
+            "2024-05-28",
+        ],
+    ));
+    // Cancelled in April.
+    transactions.extend(series(
+        "gym",
+        "FITNESS FIRST",
+        -2_000,
+        &["2024-01-05", "2024-02-05", "2024-03-05", "2024-04-05"],
+    ));
+    // A monthly transfer to the Saver, seen from both sides.
+    let transfers = ["2024-04-10", "2024-05-10", "2024-06-10"];
+    for (n, day) in transfers.iter().enumerate() {
+        let created_at = format!("{}T09:00:00+10:00", day);
+        transactions.push(
+            transaction(&format!("to-saver-{}", n), -50_000)
+                .description("Transfer to Holiday")
+                .transfer_account(SAVER)
+                .created_at(&created_at)
+                .build(),
+        );
+        transactions.push(
+            transaction(&format!("from-spending-{}", n), 50_000)
+                .description("Transfer from Spending")
+                .account(SAVER)
+                .transfer_account(SPENDING)
+                .created_at(&created_at)
+                .build(),
+        );
+    }
+    transactions.push(
+        transaction("uber", -2_500)
+            .description("Uber *Trip")
+            .held()
+            .created_at("2024-06-30T20:00:00+10:00")
+            .build(),
+    );
+    transactions
+}
+
+#[test]
+fn forecast_places_events_and_runs_the_balance_forward() {
+    let accounts = [
+        account(SPENDING, "Spending", "TRANSACTIONAL", 300_000),
+        account(SAVER, "Holiday", "SAVER", 50_000),
+    ];
+    let forecasts = CashflowForecaster::new().forecast(&accounts, &history(), date("2024-07-01"));
+    let spending = &forecasts[0];
+
+    assert_eq!(spending.account_id, SPENDING);
+    assert_eq!(spending.days.len(), 31);
+    assert_eq!(spending.days[0].date, date("2024-07-01"));
+    assert_eq!(spending.days[30].date, date("2024-07-31"));
+    assert_eq!(spending.starting_balance.value_in_base_units, 300_000);
+    assert_eq!(spending.held.value_in_base_units, -2_500);
+
+    let events: Vec<(NaiveDate, &str, i64)> = spending
+        .days
+        .iter()
+        .flat_map(|day| {
+            day.events.iter().map(move |event| {
+                (
+                    day.date,
+                    event.description.as_str(),
+                    day.balance.value_in_base_units,
+                )
+            })
+        })
+        .collect();
+    assert_eq!(
+        events,
+        [
+            // Overdue, so expected straight away.
+            (date("2024-07-01"), "TELSTRA", 294_000),
+            (date("2024-07-02"), "RAY WHITE RENT", 74_000),
+            // Already out of the available balance.
+            (date("2024-07-03"), "Uber *Trip", 74_000),
+            (date("2024-07-04"), "ACME PTY LTD", 424_000),
+            (date("2024-07-10"), "Transfer to Holiday", 374_000),
+            (date("2024-07-18"), "ACME PTY LTD", 724_000),
+            (date("2024-07-28"), "TELSTRA", 718_000),
+        ]
+    );
+    assert!(matches!(
+        &spending.days[2].events[0].kind,
+        ForecastEventKind::HeldSettlement { transaction_id } if transaction_id == "uber"
+    ));
+    assert!(matches!(
+        spending.days[1].events[0].kind,
+        ForecastEventKind::Recurring { confidence } if confidence == 1.0
+    ));
+    assert_eq!(spending.days[30].balance.value_in_base_units, 718_000);
+
+    let lowest = spending.lowest().unwrap();
+    assert_eq!(lowest.date, date("2024-07-02"));
+    assert_eq!(
+        spending.first_below(100_000).unwrap().date,
+        date("2024-07-02")
+    );
+    assert!(spending.first_below(50_000).is_none());
+
+    let saver = &forecasts[1];
+    let balances: Vec<i64> = saver
+        .days
+        .iter()
+        .map(|day| day.balance.value_in_base_units)
+        .collect();
+    assert_eq!(balances[..9], [50_000; 9]);
+    assert_eq!(balances[9..], [100_000; 22]);
+}
+
+#[test]
+fn forecast_length_and_settlement_delay_are_configurable() {
+    let accounts = [account(SPENDING, "Spending", "TRANSACTIONAL", 300_000)];
+    let forecasts = CashflowForecaster::new()
+        .days(7)
+        .settlement_days(1)
+        .forecast(&accounts, &history(), date("2024-07-01"));
+    let spending = &forecasts[0];
+
+    assert_eq!(spending.days.len(), 8);
+    let settlements: Vec<NaiveDate> = spending
+        .days
+        .iter()
+        .filter(|day| {
+            day.events
+                .iter()
+                .any(|event| matches!(event.kind, ForecastEventKind::HeldSettlement { .. }))
+        })
+        .map(|day| day.date)
+        .collect();
+    assert_eq!(settlements, [date("2024-07-01")]);
+}
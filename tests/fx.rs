@@ -0,0 +1,131 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::transaction;
+use uprusty::TransactionResource;
+use uprusty::analysis::foreign_spending;
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+fn transactions() -> Vec<TransactionResource> {
+    vec![
+        transaction("museum", -1_500)
+            .foreign("USD", -1_000, 2)
+            .created_at("2024-09-02T10:00:00-07:00")
+            .build(),
+        // Authorised at 1.50 AUD per USD, settled at 1.55.
+        transaction("hotel", -31_000)
+            .foreign("USD", -20_000, 2)
+            .settled_from_hold(-30_000)
+            .held_foreign("USD", -20_000, 2)
+            .created_at("2024-09-03T15:00:00-07:00")
+            .build(),
+        transaction("ramen", -1_000)
+            .foreign("JPY", -1_000, 0)
+            .created_at("2024-09-05T19:00:00+09:00")
+            .build(),
+        transaction("refund", 750)
+            .foreign("USD", 500, 2)
+            .created_at("2024-09-06T09:00:00-07:00")
+            .build(),
+        transaction("taxi", -4_000)
+            .foreign("USD", -2_500, 2)
+            .held()
+            .held_foreign("USD", -2_500, 2)
+            .created_at("2024-09-07T22:00:00-07:00")
+            .build(),
+        transaction("coffee", -450)
+            .created_at("2024-09-08T09:00:00+10:00")
+            .build(),
+    ]
+}
+
+#[test]
+fn rates_come_from_each_transactions_foreign_amount() {
+    let report = foreign_spending(&transactions());
+
+    let ids: Vec<&str> = report
+        .transactions
+        .iter()
+        .map(|t| t.transaction_id.as_str())
+        .collect();
+    assert_eq!(ids, ["museum", "hotel", "ramen", "refund", "taxi"]);
+
+    let rates: Vec<f64> = report.transactions.iter().map(|t| t.rate).collect();
+    for (rate, expected) in rates.iter().zip([1.5, 1.55, 0.01, 1.5, 1.6]) {
+        assert_close(*rate, expected);
+    }
+    // The local date the transaction was made, not the date in Australia.
+    assert_eq!(report.transactions[0].date, date("2024-09-02"));
+}
+
+#[test]
+fn settled_transactions_are_compared_with_their_hold() {
+    let report = foreign_spending(&transactions());
+    let find = |id: &str| {
+        report
+            .transactions
+            .iter()
+            .find(|t| t.transaction_id == id)
+            .unwrap()
+    };
+
+    let hotel = find("hotel");
+    let hold = hotel.hold.as_ref().unwrap();
+    assert_close(hold.held_rate, 1.5);
+    assert_eq!(hold.drift_in_base_units, 1_000);
+    assert_close(hotel.rate_drift().unwrap(), 0.05);
+
+    // Still held, so there is nothing to compare yet.
+    let taxi = find("taxi");
+    assert!(!taxi.settled);
+    assert!(taxi.hold.is_none());
+    assert!(find("museum").rate_drift().is_none());
+}
+
+#[test]
+fn spending_is_summarised_per_currency() {
+    let report = foreign_spending(&transactions());
+
+    let codes: Vec<&str> = report
+        .currencies
+        .iter()
+        .map(|c| c.currency_code.as_str())
+        .collect();
+    assert_eq!(codes, ["JPY", "USD"]);
+
+    let jpy = &report.currencies[0];
+    assert_eq!(jpy.transactions, 1);
+    assert_eq!(jpy.foreign_total_in_base_units, -1_000);
+    assert_close(jpy.average_rate, 0.01);
+
+    let usd = &report.currencies[1];
+    assert_eq!(usd.transactions, 4);
+    assert_eq!(usd.total_in_base_units, -1_500 - 31_000 + 750 - 4_000);
+    assert_eq!(
+        usd.foreign_total_in_base_units,
+        -1_000 - 20_000 + 500 - 2_500
+    );
+    // Weighted by amount: 372.50 AUD for 240.00 USD.
+    assert_close(usd.average_rate, 372.5 / 240.0);
+    assert_close(usd.lowest_rate, 1.5);
+    assert_close(usd.highest_rate, 1.6);
+    assert_eq!(usd.drift_in_base_units, 1_000);
+
+    let trip: Vec<&str> = report
+        .between(date("2024-09-03"), date("2024-09-05"))
+        .map(|t| t.transaction_id.as_str())
+        .collect();
+    assert_eq!(trip, ["hotel", "ramen"]);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::{SAVER, SPENDING, account, transaction};
+use std::collections::HashMap;
+use uprusty::export::{LedgerExporter, LedgerFormat};
+
+const EXTERNAL: &str = "9d8c7b6a-5f4e-4d3c-8b2a-1f0e9d8c7b6a";
+
+struct Posting {
+    account: String,
+    amount: i64,
+    currency: String,
+    price: Option<(i64, String)>,
+}
+
+fn base_units(value: &str) -> i64 {
+    (value.parse::<f64>().unwrap() * 100.0).round() as i64
+}
+
+// Postings of each entry in a Ledger journal, leaving out the balance checks.
+fn entries(journal: &str) -> Vec<Vec<Posting>> {
+    journal
+        .split("\n\n")
+        .filter(|entry| !entry.contains("Balance check") && !entry.trim().is_empty())
+        .map(|entry| {
+            entry
+                .lines()
+                .filter(|line| line.starts_with("    ") && !line.trim_start().starts_with(';'))
+                .map(|line| {
+                    let (account, amount) = line.trim().split_once("  ").unwrap();
+                    let (amount, price) = match amount.split_once(" @@ ") {
+                        Some((amount, price)) => (amount, Some(price)),
+                        None => (amount, None),
+                    };
+                    let (value, currency) = amount.split_once(' ').unwrap();
+                    Posting {
+                        account: account.to_string(),
+                        amount: base_units(value),
+                        currency: currency.to_string(),
+                        price: price.map(|price| {
+                            let (value, currency) = price.split_once(' ').unwrap();
+                            (base_units(value), currency.to_string())
+                        }),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn journal_balances_with_transfers_and_foreign_amounts() {
+    let accounts = [
+        account(SPENDING, "Spending", "TRANSACTIONAL", 10_000),
+        account(SAVER, "Holiday", "SAVER", 5_000),
+    ];
+    let transactions = [
+        // Only the outgoing half of this transfer is exported.
+        transaction("one-sided", -2_000)
+            .created_at("2024-09-01T10:00:00+10:00")
+            .transfer_account(SAVER)
+            .build(),
+        // Both halves of this one are.
+        transaction("out", -1_000)
+            .created_at("2024-09-02T10:00:00+10:00")
+            .transfer_account(SAVER)
+            .build(),
+        transaction("in", 1_000)
+            .created_at("2024-09-02T10:00:00+10:00")
+            .account(SAVER)
+            .transfer_account(SPENDING)
+            .build(),
+        // A transfer from an account that isn't exported.
+        transaction("external", 500)
+            .created_at("2024-09-03T10:00:00+10:00")
+            .account(SAVER)
+            .transfer_account(EXTERNAL)
+            .build(),
+        transaction("foreign", -500)
+            .created_at("2024-09-04T10:00:00+10:00")
+            .foreign("USD", -330, 2)
+            .category(Some("restaurants-and-cafes"), Some("good-life"))
+            .build(),
+    ];
+
+    let mut journal = Vec::new();
+    LedgerExporter::new(LedgerFormat::Hledger)
+        .write(&mut journal, &accounts, &transactions)
+        .unwrap();
+    let journal = String::from_utf8(journal).unwrap();
+    let entries = entries(&journal);
+
+    // Each entry balances in AUD, counting foreign postings at their price.
+    for postings in &entries {
+        let total: i64 = postings
+            .iter()
+            .map(|p| match &p.price {
+                Some((price, currency)) => {
+                    assert_eq!(currency, "AUD");
+                    price * p.amount.signum()
+                }
+                None => {
+                    assert_eq!(p.currency, "AUD");
+                    p.amount
+                }
+            })
+            .sum();
+        assert_eq!(total, 0, "unbalanced entry in\n{}", journal);
+    }
+
+    // The two-sided transfer is written once.
+    assert_eq!(entries.len(), 2 + 4);
+
+    // Each Up account ends at its balance.
+    let mut totals: HashMap<&str, i64> = HashMap::new();
+    for posting in entries.iter().flatten() {
+        *totals.entry(&posting.account).or_default() += posting.amount;
+    }
+    assert_eq!(totals["Assets:Up:Spending"], 10_000);
+    assert_eq!(totals["Assets:Up:Holiday"], 5_000);
+    assert!(journal.contains("    Assets:Up:Spending  0 AUD = 100.00 AUD"));
+    assert!(journal.contains("    Assets:Up:Holiday  0 AUD = 50.00 AUD"));
+
+    assert!(journal.contains("    Expenses:Good-Life:Restaurants-And-Cafes  3.30 USD @@ 5.00 AUD"));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use common::transaction;
+use uprusty::analysis::{LifecycleState, LifecycleTracker};
+
+fn time(s: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(s).unwrap()
+}
+
+const CREATED: &str = "2024-09-10T12:00:00+10:00";
+const SETTLED: &str = "2024-09-12T09:00:00+10:00";
+
+fn states(tracker: &LifecycleTracker, id: &str) -> Vec<LifecycleState> {
+    let lifecycle = tracker.get(id).unwrap();
+    lifecycle
+        .history
+        .iter()
+        .map(|change| change.state)
+        .collect()
+}
+
+#[test]
+fn held_then_settled() {
+    let tracker = LifecycleTracker::new();
+    tracker.observe(&transaction("a", -4_500).held().build(), time(CREATED));
+    tracker.observe(
+        &transaction("a", -5_200).settled_from_hold(-4_500).build(),
+        time(SETTLED),
+    );
+
+    let lifecycle = tracker.get("a").unwrap();
+    assert_eq!(
+        states(&tracker, "a"),
+        [LifecycleState::Held, LifecycleState::Settled]
+    );
+    assert_eq!(lifecycle.amount_change_in_base_units(), Some(-700));
+    assert_eq!(tracker.amount_changed().len(), 1);
+}
+
+#[test]
+fn late_held_observation_does_not_undo_settlement() {
+    let tracker = LifecycleTracker::new();
+    // The fetch for the settled event finishes before the one for the created event.
+    tracker.observe(
+        &transaction("a", -5_200).settled_from_hold(-4_500).build(),
+        time(SETTLED),
+    );
+    tracker.observe(&transaction("a", -4_500).held().build(), time(CREATED));
+
+    let lifecycle = tracker.get("a").unwrap();
+    assert_eq!(lifecycle.state(), Some(LifecycleState::Settled));
+    assert_eq!(states(&tracker, "a"), [LifecycleState::Settled]);
+    assert_eq!(lifecycle.amount_change_in_base_units(), Some(-700));
+
+    let now = time(SETTLED) + TimeDelta::days(30);
+    assert!(tracker.stale_holds(TimeDelta::days(7), now).is_empty());
+}
+
+#[test]
+fn settled_transaction_is_never_held_again() {
+    let tracker = LifecycleTracker::new();
+    tracker.observe(&transaction("a", -4_500).held().build(), time(CREATED));
+    tracker.observe(
+        &transaction("a", -4_500).settled_from_hold(-4_500).build(),
+        time(SETTLED),
+    );
+    // A newer but stale view still has the transaction held.
+    let later = time(SETTLED) + TimeDelta::hours(1);
+    tracker.observe(&transaction("a", -4_500).held().build(), later);
+
+    assert_eq!(
+        states(&tracker, "a"),
+        [LifecycleState::Held, LifecycleState::Settled]
+    );
+}
+
+#[test]
+fn late_held_observation_does_not_undo_deletion() {
+    let tracker = LifecycleTracker::new();
+    tracker.observe(&transaction("a", -4_500).held().build(), time(CREATED));
+    tracker.deleted("a", time(SETTLED));
+    tracker.observe(
+        &transaction("a", -4_500).held().build(),
+        time(CREATED) + TimeDelta::hours(1),
+    );
+
+    assert_eq!(
+        states(&tracker, "a"),
+        [LifecycleState::Held, LifecycleState::Deleted]
+    );
+    assert_eq!(tracker.never_settled().len(), 1);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::transaction;
+use uprusty::MerchantNormalizer;
+
+#[test]
+fn heuristics_clean_up_bank_text() {
+    let normalizer = MerchantNormalizer::new();
+    let cases = [
+        ("WOOLWORTHS 1234 SYDNEY NSW", "Woolworths"),
+        ("SQ *BLUE BOTTLE CAFE NSW", "Blue Bottle Cafe"),
+        ("SQ *THE GROUNDS 0412 ALEXANDRIA NSW AU", "The Grounds"),
+        ("PAYPAL *SPOTIFY", "Spotify"),
+        ("BUNNINGS #312 ALEXANDRIA", "Bunnings"),
+        ("KMART STORE 1042", "Kmart"),
+        ("7-ELEVEN 2045 MELBOURNE VIC", "7-Eleven"),
+        ("Netflix.com", "Netflix"),
+        ("UBER *TRIP xx1234", "Uber Trip"),
+    ];
+    for (text, merchant) in cases {
+        assert_eq!(normalizer.normalize(text), merchant, "{:?}", text);
+    }
+}
+
+#[test]
+fn heuristics_keep_names_that_look_like_locations_or_numbers() {
+    let normalizer = MerchantNormalizer::new();
+    let cases = [
+        ("Transport for NSW", "Transport for NSW"),
+        ("TRANSPORT FOR NSW", "Transport For Nsw"),
+        ("Boost Juice Act", "Boost Juice Act"),
+        ("Cafe 63", "Cafe 63"),
+        ("Bar 1828", "Bar 1828"),
+        ("CAFE 63 BONDI", "Cafe 63 Bondi"),
+    ];
+    for (text, merchant) in cases {
+        assert_eq!(normalizer.normalize(text), merchant, "{:?}", text);
+    }
+}
+
+#[test]
+fn rules_come_before_heuristics() {
+    let normalizer = MerchantNormalizer::new()
+        .regex_rule("(?i)^transport for nsw", "Opal")
+        .unwrap();
+    let opal = transaction("opal", -500)
+        .description("Transport for NSW")
+        .build();
+    let cafe = transaction("cafe", -500).description("Cafe 63").build();
+    assert_eq!(opal.merchant(&normalizer), "Opal");
+    assert_eq!(cafe.merchant(&normalizer), "Cafe 63");
+
+    let plain = MerchantNormalizer::without_heuristics();
+    let woolworths = transaction("woolworths", -500)
+        .description("WOOLWORTHS 1234 SYDNEY NSW")
+        .build();
+    assert_eq!(woolworths.merchant(&plain), "WOOLWORTHS 1234 SYDNEY NSW");
+}
@@ -0,0 +1,184 @@
+//! Deserialize sample payloads for every resource in `tests/fixtures/models`, and check that
+//! each model serializes back to the same JSON. A field the API sends but a model drops, or
+//! renames wrongly, shows up as a round-trip mismatch.
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use uprusty::{
+    AccountResponse, AccountsResponse, AttachmentResponse, AttachmentsResponse, CategoriesResponse,
+    CategoryResponse, ErrorResponse, TagsResponse, TransactionResponse, TransactionsResponse,
+    WebhookDeliveryLogsResponse, WebhookEventResponse, WebhookResponse, WebhooksResponse,
+};
+
+// Keys holding enums, with every value the API documents.
+const ENUMS: &[(&str, &[&str])] = &[
+    ("accountType", &["SAVER", "TRANSACTIONAL", "HOME_LOAN"]),
+    ("ownershipType", &["INDIVIDUAL", "JOINT"]),
+    ("status", &["HELD", "SETTLED"]),
+    (
+        "method",
+        &[
+            "BAR_CODE",
+            "OCR",
+            "CARD_PIN",
+            "CARD_DETAILS",
+            "CARD_ON_FILE",
+            "ECOMMERCE",
+            "MAGNETIC_STRIPE",
+            "CONTACTLESS",
+        ],
+    ),
+    (
+        "eventType",
+        &[
+            "TRANSACTION_CREATED",
+            "TRANSACTION_SETTLED",
+            "TRANSACTION_DELETED",
+            "PING",
+        ],
+    ),
+    (
+        "deliveryStatus",
+        &["DELIVERED", "UNDELIVERABLE", "BAD_RESPONSE_CODE"],
+    ),
+];
+
+fn fixture(name: &str) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/models")
+        .join(name);
+    let contents =
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {}: {}", path.display(), e));
+    serde_json::from_str(&contents).unwrap_or_else(|e| panic!("parsing {}: {}", path.display(), e))
+}
+
+// Optional fields may be sent as null or left out, and are serialized as null.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(without_nulls).collect()),
+        value => value,
+    }
+}
+
+fn round_trip<T: DeserializeOwned + Serialize>(json: &Value) -> Result<(), String> {
+    let model: T = serde_json::from_value(json.clone()).map_err(|e| e.to_string())?;
+    let serialized = serde_json::to_value(&model).map_err(|e| e.to_string())?;
+    if without_nulls(serialized.clone()) != without_nulls(json.clone()) {
+        return Err(format!(
+            "round trip changed the payload\nexpected: {}\n  actual: {}",
+            without_nulls(json.clone()),
+            without_nulls(serialized)
+        ));
+    }
+
+    let again: T = serde_json::from_value(serialized.clone()).map_err(|e| e.to_string())?;
+    let reserialized = serde_json::to_value(&again).map_err(|e| e.to_string())?;
+    if reserialized != serialized {
+        return Err("second round trip changed the payload".to_string());
+    }
+    Ok(())
+}
+
+// Replace every free-text string with `text`, every number with one derived from `number`
+// and every enum with one of its values, keeping the shape of the payload.
+fn mutate(value: &Value, key: Option<&str>, text: &str, number: i64, variant: &Index) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(k, v)| (k.clone(), mutate(v, Some(k), text, number, variant)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|v| mutate(v, key, text, number, variant))
+                .collect(),
+        ),
+        Value::String(_) => match ENUMS.iter().find(|(k, _)| Some(*k) == key) {
+            Some((_, values)) => Value::from(*variant.get(values)),
+            None => Value::from(text),
+        },
+        Value::Number(_) if key == Some("statusCode") => Value::from(number.rem_euclid(600)),
+        Value::Number(_) => Value::from(number),
+        value => value.clone(),
+    }
+}
+
+macro_rules! model_tests {
+    ($($name:ident: $model:ty => $file:literal,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn fixture_round_trips() {
+                    if let Err(e) = round_trip::<$model>(&fixture($file)) {
+                        panic!("{}: {}", $file, e);
+                    }
+                }
+
+                proptest! {
+                    #[test]
+                    fn mutated_fixture_round_trips(
+                        text in any::<String>(),
+                        number in any::<i64>(),
+                        variant in any::<Index>(),
+                    ) {
+                        let json = mutate(&fixture($file), None, &text, number, &variant);
+                        let result = round_trip::<$model>(&json);
+                        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+                    }
+                }
+            }
+        )*
+    };
+}
+
+model_tests! {
+    accounts: AccountsResponse => "accounts.json",
+    account: AccountResponse => "account.json",
+    transactions: TransactionsResponse => "transactions.json",
+    transaction: TransactionResponse => "transaction.json",
+    categories: CategoriesResponse => "categories.json",
+    category: CategoryResponse => "category.json",
+    tags: TagsResponse => "tags.json",
+    attachments: AttachmentsResponse => "attachments.json",
+    attachment: AttachmentResponse => "attachment.json",
+    webhooks: WebhooksResponse => "webhooks.json",
+    webhook_created: WebhookResponse => "webhook_created.json",
+    webhook_event_ping: WebhookEventResponse => "webhook_event_ping.json",
+    webhook_event_transaction: WebhookEventResponse => "webhook_event_transaction.json",
+    webhook_logs: WebhookDeliveryLogsResponse => "webhook_logs.json",
+}
+
+#[test]
+fn error_response_deserializes() {
+    let errors: ErrorResponse = serde_json::from_value(fixture("error.json")).unwrap();
+    assert_eq!(errors.errors.len(), 2);
+    assert_eq!(errors.errors[0].status, "401");
+    assert!(errors.errors[0].source.is_none());
+    let source = errors.errors[1].source.as_ref().unwrap();
+    assert_eq!(source.parameter.as_deref(), Some("page[size]"));
+}
+
+#[test]
+fn attachment_without_file_deserializes() {
+    let attachments: AttachmentsResponse =
+        serde_json::from_value(fixture("attachments.json")).unwrap();
+    let attributes = &attachments.data[1].attributes;
+    assert!(attributes.file_url.is_none());
+    assert!(attributes.file_url_expires_at.is_none());
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use common::{SPENDING, transaction};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::HeaderMap;
+use uprusty::webhook::{TransactionPoller, WebhookEventHandler};
+use uprusty::{
+    Client, ClientError, HttpRequest, HttpResponse, TransactionResource, Transport,
+    WebhookEventResource,
+};
+
+/// Serves whatever transactions it was last given, filtered by `filter[since]` on their
+/// creation time like the API, and remembers the path and `since` of the last request.
+#[derive(Debug, Default)]
+struct StubApi {
+    transactions: Mutex<Vec<TransactionResource>>,
+    last_request: Mutex<Option<(String, DateTime<FixedOffset>)>>,
+}
+
+impl StubApi {
+    fn serve(&self, transactions: Vec<TransactionResource>) {
+        *self.transactions.lock().unwrap() = transactions;
+    }
+
+    fn last_request(&self) -> (String, DateTime<FixedOffset>) {
+        self.last_request.lock().unwrap().clone().unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let since = request
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == "filter[since]")
+            .map(|(_, value)| DateTime::parse_from_rfc3339(&value).unwrap())
+            .unwrap();
+        *self.last_request.lock().unwrap() = Some((request.url.path().to_string(), since));
+
+        let transactions: Vec<_> = self
+            .transactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|t| DateTime::parse_from_rfc3339(&t.attributes.created_at).unwrap() >= since)
+            .cloned()
+            .collect();
+        let body = json!({ "data": transactions, "links": { "prev": null, "next": null } });
+        Ok(HttpResponse::new(
+            StatusCode::OK,
+            HeaderMap::new(),
+            serde_json::to_vec(&body)?,
+        ))
+    }
+}
+
+/// Records the events dispatched to it as `(kind, transaction_id)`.
+#[derive(Debug, Default)]
+struct Recorder(Mutex<Vec<(&'static str, String)>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<(&'static str, String)> {
+        let mut events = std::mem::take(&mut *self.0.lock().unwrap());
+        events.sort();
+        events
+    }
+}
+
+impl WebhookEventHandler for Recorder {
+    fn on_transaction_created(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(("created", transaction_id.into()));
+    }
+
+    fn on_transaction_settled(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(("settled", transaction_id.into()));
+    }
+
+    fn on_transaction_deleted(&self, transaction_id: &str, _event: &WebhookEventResource) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(("deleted", transaction_id.into()));
+    }
+}
+
+fn events(expected: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+    expected
+        .iter()
+        .map(|(kind, id)| (*kind, id.to_string()))
+        .collect()
+}
+
+// A timestamp `ago` before now.
+fn ago(ago: TimeDelta) -> String {
+    (Local::now().fixed_offset() - ago).to_rfc3339()
+}
+
+fn setup(lookback: TimeDelta) -> (Arc<StubApi>, TransactionPoller) {
+    let api = Arc::new(StubApi::default());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+    let poller = TransactionPoller::new(client).lookback(lookback);
+    (api, poller)
+}
+
+#[tokio::test]
+async fn first_poll_only_records_unless_emitting_existing() {
+    let existing = transaction("a", -100)
+        .created_at(&ago(TimeDelta::hours(1)))
+        .build();
+    let recorder = Recorder::default();
+
+    let (api, mut quiet) = setup(TimeDelta::days(7));
+    api.serve(vec![existing.clone()]);
+    assert!(quiet.poll_once(&recorder).await.unwrap().is_empty());
+    assert!(recorder.take().is_empty());
+
+    let (api, loud) = setup(TimeDelta::days(7));
+    let mut loud = loud.emit_existing(true);
+    api.serve(vec![existing]);
+    let emitted = loud.poll_once(&recorder).await.unwrap();
+    assert_eq!(emitted.len(), 1);
+    assert_eq!(recorder.take(), events(&[("created", "a")]));
+}
+
+#[tokio::test]
+async fn polls_report_created_settled_and_deleted() {
+    let (api, mut poller) = setup(TimeDelta::days(7));
+    let recorder = Recorder::default();
+    let held_at = ago(TimeDelta::hours(3));
+    let gone_at = ago(TimeDelta::hours(2));
+    let new_at = ago(TimeDelta::hours(1));
+
+    api.serve(vec![
+        transaction("held", -100)
+            .held()
+            .created_at(&held_at)
+            .build(),
+        transaction("gone", -100)
+            .held()
+            .created_at(&gone_at)
+            .build(),
+    ]);
+    poller.poll_once(&recorder).await.unwrap();
+    assert!(recorder.take().is_empty());
+
+    let latest = vec![
+        transaction("held", -100).created_at(&held_at).build(),
+        transaction("new", -100).held().created_at(&new_at).build(),
+    ];
+    api.serve(latest.clone());
+    poller.poll_once(&recorder).await.unwrap();
+    assert_eq!(
+        recorder.take(),
+        events(&[("created", "new"), ("deleted", "gone"), ("settled", "held")])
+    );
+
+    // Nothing changed since.
+    api.serve(latest);
+    assert!(poller.poll_once(&recorder).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn polls_ask_for_the_lookback_window() {
+    let (api, poller) = setup(TimeDelta::days(2));
+    let mut poller = poller.account(SPENDING);
+    let before = Local::now().fixed_offset() - TimeDelta::days(2);
+    poller.poll_once(&Recorder::default()).await.unwrap();
+    let after = Local::now().fixed_offset() - TimeDelta::days(2);
+
+    let (path, since) = api.last_request();
+    assert_eq!(path, format!("/api/v1/accounts/{}/transactions", SPENDING));
+    // `since` goes out as RFC 3339, which may drop sub-second precision.
+    assert!(since >= before - TimeDelta::seconds(1) && since <= after);
+}
+
+#[tokio::test]
+async fn transactions_leaving_the_window_are_not_deleted() {
+    let lookback = TimeDelta::days(1);
+    let (api, mut poller) = setup(lookback);
+    let recorder = Recorder::default();
+
+    // Falls out of the window between the two polls.
+    let leaving = ago(lookback - TimeDelta::milliseconds(500));
+    api.serve(vec![transaction("old", -100).created_at(&leaving).build()]);
+    poller.poll_once(&recorder).await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(poller.poll_once(&recorder).await.unwrap().is_empty());
+    assert!(recorder.take().is_empty());
+}
+
+#[tokio::test]
+async fn transactions_at_the_edge_of_the_window_are_not_deleted() {
+    let lookback = TimeDelta::days(1);
+    let (api, mut poller) = setup(lookback);
+    let recorder = Recorder::default();
+    let edge = transaction("edge", -100)
+        .created_at(&ago(lookback - TimeDelta::minutes(2)))
+        .build();
+    let inside = transaction("inside", -100)
+        .created_at(&ago(lookback - TimeDelta::minutes(10)))
+        .build();
+
+    api.serve(vec![edge.clone(), inside.clone()]);
+    poller.poll_once(&recorder).await.unwrap();
+
+    // The API's clock is ahead, so it leaves out a transaction still inside our window.
+    api.serve(vec![inside.clone()]);
+    assert!(poller.poll_once(&recorder).await.unwrap().is_empty());
+
+    // When it comes back it isn't new.
+    api.serve(vec![edge.clone(), inside]);
+    assert!(poller.poll_once(&recorder).await.unwrap().is_empty());
+
+    // Past the margin, a missing transaction was deleted.
+    api.serve(vec![edge]);
+    poller.poll_once(&recorder).await.unwrap();
+    assert_eq!(recorder.take(), events(&[("deleted", "inside")]));
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{attachment, transaction};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use uprusty::export::ReceiptArchive;
+use uprusty::transport::StatusCode;
+use uprusty::transport::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use uprusty::{Client, ClientError, HttpRequest, HttpResponse, Transport};
+
+const API: &str = "https://api.up.com.au/api/v1";
+
+/// Serves two pages of attachments, their transactions and files. The file of attachment
+/// `broken` fails with a 500 while `broken` is set.
+#[derive(Debug)]
+struct StubApi {
+    transactions: Vec<Value>,
+    broken: AtomicBool,
+    downloads: Mutex<Vec<String>>,
+}
+
+impl StubApi {
+    fn new() -> Self {
+        let coffee = |id: &str| {
+            transaction(id, -450)
+                .description("Coffee & Co, Sydney")
+                .json()
+        };
+        Self {
+            transactions: vec![coffee("a"), coffee("b"), coffee("c")],
+            broken: AtomicBool::new(true),
+            downloads: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn downloads(&self) -> Vec<String> {
+        self.downloads.lock().unwrap().clone()
+    }
+}
+
+fn json_response(body: Value) -> Result<HttpResponse, ClientError> {
+    Ok(HttpResponse::new(
+        StatusCode::OK,
+        HeaderMap::new(),
+        serde_json::to_vec(&body)?,
+    ))
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        let url = &request.url;
+        if url.host_str() == Some("files.example") {
+            let id = url.path().trim_start_matches('/').trim_end_matches(".pdf");
+            if id == "broken" && self.broken.load(Ordering::SeqCst) {
+                return Ok(HttpResponse::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    HeaderMap::new(),
+                    Vec::new(),
+                ));
+            }
+            self.downloads.lock().unwrap().push(id.to_string());
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/pdf"));
+            return Ok(HttpResponse::new(
+                StatusCode::OK,
+                headers,
+                format!("receipt {}", id).into_bytes(),
+            ));
+        }
+
+        let path = url.path().trim_start_matches("/api/v1/");
+        if path == "attachments" {
+            return if url.query_pairs().any(|(key, _)| key == "page[after]") {
+                json_response(json!({
+                    "data": [attachment("broken", "c")],
+                    "links": { "prev": null, "next": null },
+                }))
+            } else {
+                json_response(json!({
+                    "data": [attachment("first", "a"), attachment("second", "b")],
+                    "links": {
+                        "prev": null,
+                        "next": format!("{}/attachments?page[after]=second", API),
+                    },
+                }))
+            };
+        }
+        if let Some(id) = path.strip_prefix("transactions/")
+            && let Some(transaction) = self.transactions.iter().find(|t| t["id"] == id)
+        {
+            return json_response(json!({ "data": transaction }));
+        }
+        Ok(HttpResponse::new(
+            StatusCode::NOT_FOUND,
+            HeaderMap::new(),
+            Vec::new(),
+        ))
+    }
+}
+
+fn archive_directory(name: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("uprusty-receipts-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+}
+
+fn file_names(directory: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn archive_writes_a_manifest_and_resumes() {
+    let directory = archive_directory("resume");
+    let api = Arc::new(StubApi::new());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+    let archive = ReceiptArchive::new(&directory).page_size(2);
+
+    let summary = archive.export(&client).await.unwrap();
+    assert_eq!(summary.downloaded, 2);
+    assert_eq!(summary.already_archived, 0);
+    let failed: Vec<&str> = summary.failed.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(failed, ["broken"]);
+
+    // The failed download leaves no partial file behind; same-named receipts get a suffix.
+    assert_eq!(
+        file_names(&directory),
+        [
+            "2024-09-10_Coffee-Co-Sydney_-4.50-2.pdf",
+            "2024-09-10_Coffee-Co-Sydney_-4.50.pdf",
+            "manifest.csv",
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(directory.join("2024-09-10_Coffee-Co-Sydney_-4.50-2.pdf")).unwrap(),
+        "receipt second"
+    );
+    assert_eq!(
+        fs::read_to_string(directory.join("manifest.csv")).unwrap(),
+        "attachment_id,transaction_id,file_name,date,description,amount,currency\n\
+         first,a,2024-09-10_Coffee-Co-Sydney_-4.50.pdf,2024-09-10,\"Coffee & Co, Sydney\",-4.50,AUD\n\
+         second,b,2024-09-10_Coffee-Co-Sydney_-4.50-2.pdf,2024-09-10,\"Coffee & Co, Sydney\",-4.50,AUD\n"
+    );
+
+    // Running again only fetches what is missing from the manifest.
+    api.broken.store(false, Ordering::SeqCst);
+    let summary = archive.export(&client).await.unwrap();
+    assert_eq!(summary.downloaded, 1);
+    assert_eq!(summary.already_archived, 2);
+    assert!(summary.failed.is_empty());
+    assert_eq!(api.downloads(), ["first", "second", "broken"]);
+
+    let manifest = fs::read_to_string(directory.join("manifest.csv")).unwrap();
+    assert_eq!(manifest.lines().count(), 4);
+    assert!(
+        manifest
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("broken,c,2024-09-10_Coffee-Co-Sydney_-4.50-3.pdf,")
+    );
+    assert!(
+        !file_names(&directory)
+            .iter()
+            .any(|name| name.ends_with(".part"))
+    );
+
+    fs::remove_dir_all(&directory).unwrap();
+}
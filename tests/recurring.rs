@@ -0,0 +1,162 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{SAVER, transaction};
+use uprusty::TransactionResource;
+use uprusty::analysis::{Frequency, RecurringDetector};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+/// One transaction per date, at 9am Brisbane time.
+fn series(
+    id: &str,
+    description: &str,
+    amounts: &[i64],
+    dates: &[&str],
+) -> Vec<TransactionResource> {
+    dates
+        .iter()
+        .zip(amounts.iter().cycle())
+        .enumerate()
+        .map(|(n, (day, amount))| {
+            transaction(&format!("{}-{}", id, n), *amount)
+                .description(description)
+                .created_at(&format!("{}T09:00:00+10:00", day))
+                .build()
+        })
+        .collect()
+}
+
+fn history() -> Vec<TransactionResource> {
+    let mut transactions = Vec::new();
+    // Rent at the end of each month, going up in June.
+    transactions.extend(series(
+        "rent",
+        "RAY WHITE RENT",
+        &[-220_000, -220_000, -220_000, -220_000, -220_000, -230_000],
+        &[
+            "2024-01-31",
+            "2024-02-29",
+            "2024-03-31",
+            "2024-04-30",
+            "2024-05-31",
+            "2024-06-30",
+        ],
+    ));
+    transactions.extend(series(
+        "salary",
+        "ACME PTY LTD",
+        &[350_000],
+        &[
+            "2024-04-11",
+            "2024-04-25",
+            "2024-05-09",
+            "2024-05-23",
+            "2024-06-06",
+            "2024-06-20",
+        ],
+    ));
+    transactions.extend(series(
+        "coffee",
+        "Market Lane Coffee",
+        &[-450],
+        &[
+            "2024-06-01",
+            "2024-06-03",
+            "2024-06-10",
+            "2024-06-11",
+            "2024-06-25",
+        ],
+    ));
+    transactions
+}
+
+#[test]
+fn month_end_and_fortnightly_payments_are_detected() {
+    let payments = RecurringDetector::new().detect(&history());
+
+    let merchants: Vec<&str> = payments.iter().map(|p| p.merchant.as_str()).collect();
+    assert_eq!(merchants, ["Acme Pty Ltd", "Ray White Rent"]);
+
+    let salary = &payments[0];
+    assert_eq!(salary.frequency, Frequency::Fortnightly);
+    assert!(salary.is_income());
+    assert_eq!(salary.last_date, date("2024-06-20"));
+    assert_eq!(salary.next_expected, date("2024-07-04"));
+    assert_eq!(salary.confidence, 1.0);
+    assert!(salary.price_change.is_none());
+
+    let rent = &payments[1];
+    assert_eq!(rent.frequency, Frequency::Monthly);
+    assert!(!rent.is_income());
+    assert_eq!(rent.transaction_ids.len(), 6);
+    assert_eq!(rent.amount.value_in_base_units, -230_000);
+    // One calendar month on from the last payment, clamped to the length of the month.
+    assert_eq!(rent.next_expected, date("2024-07-30"));
+    // The rise is within the amount tolerance, so the series holds together.
+    assert_eq!(rent.confidence, 1.0);
+    let change = rent.price_change.as_ref().unwrap();
+    assert_eq!(change.previous.value_in_base_units, -220_000);
+    assert_eq!(change.current.value_in_base_units, -230_000);
+}
+
+#[test]
+fn upcoming_lists_expected_dates_until_payments_lapse() {
+    let detector = RecurringDetector::new();
+
+    let charges = detector.upcoming(&history(), date("2024-07-01"), 31);
+    let upcoming: Vec<(NaiveDate, &str)> = charges
+        .iter()
+        .map(|charge| (charge.expected_on, charge.description.as_str()))
+        .collect();
+    assert_eq!(
+        upcoming,
+        [
+            (date("2024-07-04"), "ACME PTY LTD"),
+            (date("2024-07-18"), "ACME PTY LTD"),
+            (date("2024-07-30"), "RAY WHITE RENT"),
+            (date("2024-08-01"), "ACME PTY LTD"),
+        ]
+    );
+
+    // Weeks without the salary or rent turning up: both look cancelled.
+    assert!(
+        detector
+            .upcoming(&history(), date("2024-08-20"), 30)
+            .is_empty()
+    );
+}
+
+#[test]
+fn retries_count_once_and_transfers_are_opt_in() {
+    let mut transactions = series(
+        "gym",
+        "FITNESS FIRST",
+        &[-2_000],
+        &["2024-03-05", "2024-04-05", "2024-04-05", "2024-05-05"],
+    );
+    let savings: Vec<TransactionResource> = ["2024-03-10", "2024-04-10", "2024-05-10"]
+        .iter()
+        .enumerate()
+        .map(|(n, day)| {
+            transaction(&format!("savings-{}", n), -50_000)
+                .description("Transfer to Holiday")
+                .transfer_account(SAVER)
+                .created_at(&format!("{}T09:00:00+10:00", day))
+                .build()
+        })
+        .collect();
+    transactions.extend(savings);
+
+    let payments = RecurringDetector::new().detect(&transactions);
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].transaction_ids, ["gym-0", "gym-2", "gym-3"]);
+
+    let payments = RecurringDetector::new()
+        .include_transfers(true)
+        .detect(&transactions);
+    let merchants: Vec<&str> = payments.iter().map(|p| p.merchant.as_str()).collect();
+    assert_eq!(merchants, ["Fitness First", "Transfer to Holiday"]);
+}
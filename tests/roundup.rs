@@ -0,0 +1,148 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{SAVER, SPENDING, transaction};
+use uprusty::TransactionResource;
+use uprusty::analysis::{Period, RoundUpAnalyzer};
+
+const HOLIDAY: &str = "f3b2a1c0-9d8e-4f7a-b6c5-d4e3f2a1b0c9";
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn round_up_transfer(id: &str, amount: i64, saver: &str, created_at: &str) -> TransactionResource {
+    transaction(id, amount)
+        .description("Round Up")
+        .account(saver)
+        .transfer_account(SPENDING)
+        .created_at(created_at)
+        .build()
+}
+
+fn transactions() -> Vec<TransactionResource> {
+    vec![
+        transaction("coffee", -450)
+            .round_up(-50, None)
+            .created_at("2024-09-02T09:00:00+10:00")
+            .build(),
+        round_up_transfer("coffee-round-up", 50, SAVER, "2024-09-02T09:01:00+10:00"),
+        // Same amount and within the window, but further away than the one above.
+        round_up_transfer("decoy", 50, SAVER, "2024-09-04T09:00:00+10:00"),
+        transaction("groceries", -12_330)
+            .round_up(-170, Some(-100))
+            .created_at("2024-09-20T18:00:00+10:00")
+            .build(),
+        round_up_transfer(
+            "groceries-round-up",
+            170,
+            HOLIDAY,
+            "2024-09-21T09:00:00+10:00",
+        ),
+        transaction("lunch", -1_990)
+            .round_up(-10, None)
+            .created_at("2024-10-01T12:00:00+10:00")
+            .build(),
+        // Too late for lunch's round-up, and for a different amount anyway.
+        round_up_transfer("stray", 30, SAVER, "2024-10-05T09:00:00+10:00"),
+        transaction("rent", -200_000)
+            .created_at("2024-09-15T09:00:00+10:00")
+            .build(),
+    ]
+}
+
+#[test]
+fn round_ups_are_matched_to_their_transfers() {
+    let report = RoundUpAnalyzer::new().analyze(&transactions());
+
+    let matches: Vec<_> = report
+        .matches
+        .iter()
+        .map(|m| {
+            (
+                m.purchase_id.as_str(),
+                m.transfer_id.as_deref(),
+                m.saver_account_id.as_deref(),
+                m.rounded_up,
+                m.boosted,
+            )
+        })
+        .collect();
+    assert_eq!(
+        matches,
+        [
+            ("coffee", Some("coffee-round-up"), Some(SAVER), 50, 0),
+            (
+                "groceries",
+                Some("groceries-round-up"),
+                Some(HOLIDAY),
+                170,
+                100
+            ),
+            ("lunch", None, None, 10, 0),
+        ]
+    );
+    assert_eq!(report.unmatched_transfers, ["decoy", "stray"]);
+    let unmatched: Vec<&str> = report
+        .unmatched_purchases()
+        .map(|m| m.purchase_id.as_str())
+        .collect();
+    assert_eq!(unmatched, ["lunch"]);
+    assert_eq!(report.total_rounded_up(), 230);
+    assert_eq!(report.total_boosted(), 100);
+}
+
+#[test]
+fn round_ups_are_totalled_per_saver_and_period() {
+    let report = RoundUpAnalyzer::new().analyze(&transactions());
+
+    let savers: Vec<(&str, usize, i64, i64)> = report
+        .savers
+        .iter()
+        .map(|s| (s.account_id.as_str(), s.transfers, s.rounded_up, s.boosted))
+        .collect();
+    assert_eq!(savers, [(SAVER, 1, 50, 0), (HOLIDAY, 1, 170, 100)]);
+
+    let months: Vec<(NaiveDate, usize, i64, i64)> = report
+        .periods
+        .iter()
+        .map(|p| (p.start, p.purchases, p.rounded_up, p.boosted))
+        .collect();
+    assert_eq!(
+        months,
+        [
+            (date("2024-09-01"), 2, 220, 100),
+            (date("2024-10-01"), 1, 10, 0),
+        ]
+    );
+
+    let weeks: Vec<NaiveDate> = RoundUpAnalyzer::new()
+        .period(Period::Week)
+        .analyze(&transactions())
+        .periods
+        .iter()
+        .map(|p| p.start)
+        .collect();
+    assert_eq!(
+        weeks,
+        [date("2024-09-02"), date("2024-09-16"), date("2024-09-30")]
+    );
+}
+
+#[test]
+fn transfers_outside_the_window_are_not_matched() {
+    let report = RoundUpAnalyzer::new()
+        .match_window(chrono::TimeDelta::minutes(30))
+        .analyze(&transactions());
+
+    let transfers: Vec<Option<&str>> = report
+        .matches
+        .iter()
+        .map(|m| m.transfer_id.as_deref())
+        .collect();
+    assert_eq!(transfers, [Some("coffee-round-up"), None, None]);
+    assert_eq!(
+        report.unmatched_transfers,
+        ["decoy", "groceries-round-up", "stray"]
+    );
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use async_trait::async_trait;
+use common::{SAVER, transaction};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+use uprusty::transport::header::HeaderMap;
+use uprusty::transport::{Method, StatusCode};
+use uprusty::webhook::WebhookEventHandler;
+use uprusty::{
+    BatchOptions, CardPurchaseMethod, Client, ClientError, HttpRequest, HttpResponse, Rule,
+    RuleEngine, RuleWebhookHandler, TransactionResource, Transport, WebhookEventResource,
+};
+
+fn coffee() -> common::TransactionBuilder {
+    transaction("coffee", -450).description("Market Lane Coffee")
+}
+
+fn engine() -> RuleEngine {
+    RuleEngine::new()
+        .rule(
+            Rule::new("coffee")
+                .description_matches("(?i)coffee")
+                .unwrap()
+                .amount_in_range(-1_000..0)
+                .set_category("restaurants-and-cafes")
+                .add_tags(&["Coffee"]),
+        )
+        .rule(
+            Rule::new("small purchases")
+                .amount_in_range(-2_000..0)
+                .set_category("good-life")
+                .add_tags(&["Small", "Coffee"]),
+        )
+}
+
+#[test]
+fn conditions_match_transaction_details() {
+    let mut json = coffee()
+        .description("Coffee")
+        .foreign("USD", -300, 2)
+        .account(SAVER)
+        .json();
+    json["attributes"]["rawText"] = json!("MARKET LANE 1234 MELBOURNE");
+    json["attributes"]["cardPurchaseMethod"] =
+        json!({ "method": "CONTACTLESS", "cardNumberSuffix": "1234" });
+    json["attributes"]["performingCustomer"] = json!({ "displayName": "Bree" });
+    let transaction: TransactionResource = serde_json::from_value(json).unwrap();
+
+    let matches = |rule: Rule| rule.matches(&transaction);
+    assert!(matches(Rule::new("any")));
+    assert!(matches(
+        Rule::new("raw text")
+            .description_matches("^MARKET LANE")
+            .unwrap()
+    ));
+    assert!(!matches(
+        Rule::new("text").description_matches("^Tea").unwrap()
+    ));
+    assert!(matches(Rule::new("amount").amount_in_range(-450..=-450)));
+    assert!(!matches(
+        Rule::new("amount")
+            .amount_in_range(-450..0)
+            .amount_in_range(..-450)
+    ));
+    assert!(matches(
+        Rule::new("tap").card_purchase_method(CardPurchaseMethod::Contactless)
+    ));
+    assert!(!matches(
+        Rule::new("online").card_purchase_method(CardPurchaseMethod::Ecommerce)
+    ));
+    assert!(matches(Rule::new("account").account(SAVER)));
+    assert!(!matches(Rule::new("account").account("other")));
+    assert!(matches(Rule::new("overseas").foreign_currency(None)));
+    assert!(matches(Rule::new("usd").foreign_currency(Some("usd"))));
+    assert!(!matches(Rule::new("nzd").foreign_currency(Some("NZD"))));
+    assert!(matches(Rule::new("bree").performing_customer("Bree")));
+    assert!(!matches(Rule::new("other").performing_customer("Alex")));
+
+    let local = coffee().build();
+    assert!(!Rule::new("overseas").foreign_currency(None).matches(&local));
+    assert!(
+        !Rule::new("bree")
+            .performing_customer("Bree")
+            .matches(&local)
+    );
+}
+
+#[test]
+fn first_matching_rule_decides_the_category_and_tags_combine() {
+    let plan = engine().plan(&coffee().build()).unwrap();
+    assert_eq!(plan.rules, ["coffee", "small purchases"]);
+    assert_eq!(plan.category.as_deref(), Some("restaurants-and-cafes"));
+    assert_eq!(plan.tags, ["Coffee", "Small"]);
+
+    let lunch = transaction("lunch", -1_800).description("Lunch").build();
+    let plan = engine().plan(&lunch).unwrap();
+    assert_eq!(plan.rules, ["small purchases"]);
+    assert_eq!(plan.category.as_deref(), Some("good-life"));
+
+    let rent = transaction("rent", -200_000).build();
+    assert!(engine().plan(&rent).is_none());
+}
+
+#[test]
+fn plan_leaves_out_changes_already_made() {
+    let done = coffee()
+        .category(Some("restaurants-and-cafes"), Some("good-life"))
+        .tags(&["Coffee", "Small"])
+        .build();
+    let plan = engine().plan(&done).unwrap();
+    assert!(plan.is_empty(), "{:?}", plan);
+
+    let mut json = coffee().json();
+    json["attributes"]["isCategorizable"] = json!(false);
+    let uncategorizable: TransactionResource = serde_json::from_value(json).unwrap();
+    assert_eq!(engine().plan(&uncategorizable).unwrap().category, None);
+
+    // Tags stop at Up's limit.
+    let full = coffee().tags(&["a", "b", "c", "d", "e"]).build();
+    assert_eq!(engine().plan(&full).unwrap().tags, ["Coffee"]);
+}
+
+/// Accepts every change, serves `transaction` for GETs, and records what was changed.
+#[derive(Debug)]
+struct StubApi {
+    transaction: Value,
+    changes: Mutex<Vec<(Method, String, Value)>>,
+}
+
+impl StubApi {
+    fn new(transaction: Value) -> Arc<Self> {
+        Arc::new(Self {
+            transaction,
+            changes: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn changes(&self) -> Vec<(Method, String, Value)> {
+        self.changes.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        if request.method == Method::GET {
+            let body = serde_json::to_vec(&json!({ "data": self.transaction }))?;
+            return Ok(HttpResponse::new(StatusCode::OK, HeaderMap::new(), body));
+        }
+
+        let path = request
+            .url
+            .path()
+            .trim_start_matches("/api/v1/")
+            .to_string();
+        let body = serde_json::from_slice(request.body.as_deref().unwrap_or_default())?;
+        self.changes
+            .lock()
+            .unwrap()
+            .push((request.method, path, body));
+        Ok(HttpResponse::new(
+            StatusCode::NO_CONTENT,
+            HeaderMap::new(),
+            Vec::new(),
+        ))
+    }
+}
+
+fn categorize(id: &str, category: &str) -> (Method, String, Value) {
+    (
+        Method::PATCH,
+        format!("transactions/{}/relationships/category", id),
+        json!({ "data": { "type": "categories", "id": category } }),
+    )
+}
+
+fn tag(id: &str, tags: &[&str]) -> (Method, String, Value) {
+    let data: Vec<Value> = tags
+        .iter()
+        .map(|tag| json!({ "type": "tags", "id": tag }))
+        .collect();
+    (
+        Method::POST,
+        format!("transactions/{}/relationships/tags", id),
+        json!({ "data": data }),
+    )
+}
+
+#[tokio::test]
+async fn apply_all_only_changes_what_the_plans_call_for() {
+    let api = StubApi::new(Value::Null);
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+    let transactions = [
+        coffee().build(),
+        transaction("rent", -200_000).build(),
+        transaction("lunch", -1_800)
+            .category(Some("good-life"), None)
+            .build(),
+        transaction("done", -300)
+            .category(Some("restaurants-and-cafes"), Some("good-life"))
+            .description("Coffee")
+            .tags(&["Coffee", "Small"])
+            .build(),
+    ];
+
+    let report = engine()
+        .apply_all(&client, &transactions, BatchOptions::with_concurrency(1))
+        .await;
+
+    // Transactions without changes to make are left out of the report.
+    assert_eq!(report.results.len(), 2);
+    let ids: Vec<&str> = report.succeeded().collect();
+    assert_eq!(ids, ["coffee", "lunch"]);
+    assert_eq!(
+        api.changes(),
+        [
+            categorize("coffee", "restaurants-and-cafes"),
+            tag("coffee", &["Coffee", "Small"]),
+            tag("lunch", &["Small", "Coffee"]),
+        ]
+    );
+}
+
+fn created_event(transaction_id: &str) -> WebhookEventResource {
+    serde_json::from_value(json!({
+        "type": "webhook-events",
+        "id": "event",
+        "attributes": {
+            "eventType": "TRANSACTION_CREATED",
+            "createdAt": "2024-09-15T12:00:00+10:00",
+        },
+        "relationships": {
+            "webhook": { "data": { "type": "webhooks", "id": "webhook" } },
+            "transaction": { "data": { "type": "transactions", "id": transaction_id } },
+        },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn webhook_handler_applies_rules_to_new_transactions() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let api = StubApi::new(coffee().json());
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+
+    let (results, received) = mpsc::channel();
+    let handler = RuleWebhookHandler::new(engine(), client, runtime.handle().clone())
+        .on_result(move |id, result| results.send((id.to_string(), result)).unwrap());
+    handler.on_transaction_created("coffee", &created_event("coffee"));
+
+    let (id, result) = received.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(id, "coffee");
+    let plan = result.unwrap().unwrap();
+    assert_eq!(plan.rules, ["coffee", "small purchases"]);
+    assert_eq!(
+        api.changes(),
+        [
+            categorize("coffee", "restaurants-and-cafes"),
+            tag("coffee", &["Coffee", "Small"]),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use async_trait::async_trait;
+use common::transaction;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use uprusty::transport::header::HeaderMap;
+use uprusty::transport::{Method, StatusCode};
+use uprusty::{Client, ClientError, HttpRequest, HttpResponse, TagsExt, Transport};
+
+/// Serves one transaction with `tags`, and records the tags posted or deleted.
+#[derive(Debug)]
+struct StubApi {
+    transaction: Value,
+    changes: Mutex<Vec<(Method, Vec<String>)>>,
+    requests: AtomicUsize,
+}
+
+impl StubApi {
+    fn with_tags(tags: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            transaction: transaction("t", -1_000).tags(tags).json(),
+            changes: Mutex::new(Vec::new()),
+            requests: AtomicUsize::new(0),
+        })
+    }
+
+    fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    fn changes(&self) -> Vec<(Method, Vec<String>)> {
+        self.changes.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for StubApi {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, ClientError> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        if request.method == Method::GET {
+            let body = serde_json::to_vec(&json!({ "data": self.transaction }))?;
+            return Ok(HttpResponse::new(StatusCode::OK, HeaderMap::new(), body));
+        }
+
+        let body: Value = serde_json::from_slice(request.body.as_deref().unwrap_or_default())?;
+        let tags = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tag| tag["id"].as_str().unwrap().to_string())
+            .collect();
+        self.changes.lock().unwrap().push((request.method, tags));
+        Ok(HttpResponse::new(
+            StatusCode::NO_CONTENT,
+            HeaderMap::new(),
+            Vec::new(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn adding_tags_is_a_single_post() {
+    let api = StubApi::with_tags(&["a", "b", "c", "d", "e"]);
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+
+    client
+        .add_tags_to_transaction("t", vec!["f", "g"])
+        .await
+        .unwrap();
+    assert_eq!(api.requests(), 1);
+    assert_eq!(
+        api.changes(),
+        [(Method::POST, vec!["f".to_string(), "g".to_string()])]
+    );
+
+    let error = client
+        .add_tags_to_transaction("t", vec!["1", "2", "3", "4", "5", "6", "7"])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::TooManyTags { count: 7, limit: 6 }
+    ));
+    assert_eq!(api.requests(), 1);
+}
+
+#[tokio::test]
+async fn set_transaction_tags_removes_before_adding() {
+    let api = StubApi::with_tags(&["a", "b", "c", "d", "e", "f"]);
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+
+    let changes = client
+        .set_transaction_tags("t", vec!["a", "b", "c", "d", "e", "g"])
+        .await
+        .unwrap();
+
+    assert_eq!(changes.added, ["g"]);
+    assert_eq!(changes.removed, ["f"]);
+    assert_eq!(
+        api.changes(),
+        [
+            (Method::DELETE, vec!["f".to_string()]),
+            (Method::POST, vec!["g".to_string()]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn set_transaction_tags_checks_the_full_set_before_any_request() {
+    let api = StubApi::with_tags(&["a"]);
+    let client = Client::with_transport("up:yeah:test", Arc::clone(&api)).unwrap();
+
+    let error = client
+        .set_transaction_tags("t", vec!["a", "b", "c", "d", "e", "f", "g", "a"])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        ClientError::TooManyTags { count: 7, limit: 6 }
+    ));
+    assert_eq!(api.requests(), 0);
+}